@@ -213,12 +213,19 @@ fn hard_facted_complex_card_counter(
                         let drawn_red = red_count >= 1 - er.passed_blues();
                         let good_liberals = match er {
                             Election(eg) => {
+                                // unknown claims match any draw
                                 let president = !hard_confirmed_libs.contains(&eg.president)
-                                    || eg.president_claimed_blues == blue_count;
+                                    || eg
+                                        .president_claimed_blues
+                                        .is_none_or(|blues| blues == blue_count);
                                 let chancellor_blue = !hard_confirmed_libs.contains(&eg.chancellor)
-                                    || eg.chancellor_claimed_blues <= blue_count;
+                                    || eg
+                                        .chancellor_claimed_blues
+                                        .is_none_or(|blues| blues <= blue_count);
                                 let chancellor_red = !hard_confirmed_libs.contains(&eg.chancellor)
-                                    || 2 - eg.chancellor_claimed_blues <= red_count;
+                                    || eg
+                                        .chancellor_claimed_blues
+                                        .is_none_or(|blues| 2 - blues <= red_count);
                                 president && chancellor_blue && chancellor_red
                             },
                             TopDeck(_, _) => true
@@ -272,13 +279,17 @@ pub(crate) fn complex_card_counter(
                         let good_liberals = match er {
                             Election(eg) => {
                                 let president = !path_assumed_liberals.contains(&eg.president)
-                                    || er.seen_blues() == blue_count; // need to use seen_blues() here because of peek-and-burns
+                                    || er.seen_blues().is_none_or(|blues| blues == blue_count); // need to use seen_blues() here because of peek-and-burns
                                 let chancellor_blue = !path_assumed_liberals
                                     .contains(&eg.chancellor)
-                                    || eg.chancellor_claimed_blues <= blue_count;
+                                    || eg
+                                        .chancellor_claimed_blues
+                                        .is_none_or(|blues| blues <= blue_count);
                                 let chancellor_red = !path_assumed_liberals
                                     .contains(&eg.chancellor)
-                                    || 2 - eg.chancellor_claimed_blues <= red_count;
+                                    || eg
+                                        .chancellor_claimed_blues
+                                        .is_none_or(|blues| 2 - blues <= red_count);
                                 president && chancellor_blue && chancellor_red
                             },
                             TopDeck(_, _) => true
@@ -293,8 +304,8 @@ pub(crate) fn complex_card_counter(
                     .iter()
                     .scan(0, |offset, er| {
                         let (drawn, _discarded) = er.cards_total_drawn_discarded();
-                        let ret =
-                            count_policies(d, *offset, drawn, Policy::Liberal) == er.seen_blues();
+                        let ret = Some(count_policies(d, *offset, drawn, Policy::Liberal))
+                            == er.seen_blues();
                         *offset += drawn;
                         Some(ret)
                     })
@@ -313,18 +324,98 @@ pub(crate) fn complex_card_counter(
             .actual_decks
             .iter()
             .filter(|d| {
-                count_policies(
+                Some(count_policies(
                     d,
                     target_offset,
                     new_hypothesis.cards_total_drawn_discarded().0,
                     Policy::Liberal
-                ) == new_hypothesis.seen_blues()
+                )) == new_hypothesis.seen_blues()
             })
             .count(),
         num_checked : decks.actual_decks.len()
     }
 }
 
+/// A statement about the amount of liberal policies among `length` cards
+/// starting at `offset` in a shuffled deck. Statements without a `player` are
+/// known to be true, the others are claims that may be lies.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct WindowConstraint {
+    pub player : Option<PlayerID>,
+    pub offset : usize,
+    pub length : usize,
+    pub min_blues : usize,
+    pub max_blues : usize
+}
+
+impl WindowConstraint {
+    fn holds(&self, deck : &Vec<Policy>) -> bool {
+        let blues = count_policies(deck, self.offset, self.length, Policy::Liberal);
+        self.min_blues <= blues && blues <= self.max_blues
+    }
+}
+
+/// Finds the minimal sets of players that need to have lied for the claims to
+/// be consistent with any deck satisfying all known constraints. An empty set
+/// means everybody could have told the truth and no set at all means that not
+/// even the known constraints can be satisfied.
+#[cached]
+pub(crate) fn minimal_liar_sets(
+    num_total_lib : usize,
+    num_total_fasc : usize,
+    constraints : Vec<WindowConstraint>
+) -> Vec<BTreeSet<PlayerID>> {
+    let liar_sets : BTreeSet<BTreeSet<PlayerID>> = generate_internal(num_total_lib, num_total_fasc)
+        .actual_decks
+        .iter()
+        .filter(|d| {
+            constraints
+                .iter()
+                .filter(|c| c.player.is_none())
+                .all(|c| c.holds(d))
+        })
+        .map(|d| {
+            constraints
+                .iter()
+                .filter(|c| !c.holds(d))
+                .filter_map(|c| c.player)
+                .collect()
+        })
+        .collect();
+
+    liar_sets
+        .iter()
+        .filter(|liars| {
+            !liar_sets
+                .iter()
+                .any(|other| other != *liars && other.is_subset(liars))
+        })
+        .cloned()
+        .collect()
+}
+
+/// Counts the decks satisfying all constraints by the amount of liberal
+/// policies among the `length` cards starting at `offset`.
+#[cached]
+pub(crate) fn constrained_window_histogram(
+    num_total_lib : usize,
+    num_total_fasc : usize,
+    constraints : Vec<WindowConstraint>,
+    offset : usize,
+    length : usize
+) -> BTreeMap<usize, usize> {
+    generate_internal(num_total_lib, num_total_fasc)
+        .actual_decks
+        .iter()
+        .filter(|d| constraints.iter().all(|c| c.holds(d)))
+        .map(|d| count_policies(d, offset, length, Policy::Liberal))
+        .sorted()
+        .group_by(|x| *x)
+        .into_iter()
+        .map(|(k, v)| (k, v.count()))
+        .collect()
+}
+
 #[cached]
 pub(crate) fn next_blues_count(
     num_total_lib : usize,
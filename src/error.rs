@@ -1,7 +1,8 @@
 use image::ImageError;
 
 use crate::{
-    players::{PlayerInfos, PlayerManager},
+    players::{PlayerInfos, PlayerManager, PresidentialAction},
+    policy::Policy,
     PlayerID
 };
 use std::{fmt, io, str};
@@ -26,11 +27,57 @@ pub(crate) enum Error {
     TooShortPatternError { have : usize, requested : usize },
     ReplError(repl_rs::Error),
     LogicalInconsistency,
+    /// no deck order agrees with the claims assumed to be true
+    ImpossibleDeck,
     BadPlayerCount(usize),
     BadFactIndex(usize),
     NotEligibleChancellor(usize, PlayerInfos),
     NotEligiblePresident(usize, PlayerInfos),
-    BadJsonConversion(serde_json::Error)
+    BadJsonConversion(serde_json::Error),
+    MissingLogToken(&'static str),
+    UnexpectedLogToken(String),
+    DanglingPresidentialAction,
+    PresidentialActionMismatch {
+        granted : PresidentialAction,
+        provided : PresidentialAction
+    },
+    GameAlreadyDecided,
+    LogImportFailed(String),
+    BadGovernmentIndex(usize),
+    BadPresidentialPower(String),
+    BadStrategy {
+        found : String,
+        expected : &'static str
+    },
+    NotAFact(String),
+    UnknownBranch(String),
+    BranchExists(String),
+    BadExpression {
+        column : usize,
+        found : String
+    },
+    BadConfidence(String),
+    ParseGovernmentIndex(String),
+    BadReadIndex(usize),
+    TooManyReads,
+    BadPerspective(&'static str),
+    DiscardNotDrawn(Policy),
+    SelfTargetedPower(PresidentialAction),
+    RepeatedInvestigation(PlayerID, PlayerInfos),
+    UnexpectedPowerArgument {
+        granted : PresidentialAction,
+        argument : String
+    },
+    UnknownEnactedPolicy,
+    EmptyPlayerSet,
+    FascistCountExceedsPlayers {
+        count : usize,
+        players : usize
+    },
+    ObservedDeckExceedsCirculation {
+        observed : usize,
+        circulation : usize
+    }
 }
 
 impl From<repl_rs::Error> for Error {
@@ -83,7 +130,13 @@ impl fmt::Display for Error {
             Error::FileSystemError(fserror) => write!(f, "Filesystem error: {fserror}"),
             Error::LogicalInconsistency => write!(
                 f,
-                "Detected a logical inconsistency, check your fact database to debug it."
+                "Detected a logical inconsistency, run diagnose with the same filter arguments to \
+                 find the contradicting facts."
+            ),
+            Error::ImpossibleDeck => write!(
+                f,
+                "No order of the cards in the deck is consistent with the claims assumed to be \
+                 true."
             ),
             Error::ParseNameError(name) => {
                 write!(f, "Failed to associate \"{name}\" with a player's name.")
@@ -129,7 +182,105 @@ impl fmt::Display for Error {
                 "Player {} cannot possibly have become president.",
                 pi.format_name(*suggestion)
             ),
-            Error::BadJsonConversion(error) => write!(f, "{error}")
+            Error::BadJsonConversion(error) => write!(f, "{error}"),
+            Error::MissingLogToken(expected) => {
+                write!(f, "Expected a {expected} but the line ended.")
+            },
+            Error::UnexpectedLogToken(found) => write!(f, "Did not expect \"{found}\" here."),
+            Error::DanglingPresidentialAction => write!(
+                f,
+                "Found a presidential action that doesn't directly follow a government."
+            ),
+            Error::PresidentialActionMismatch { granted, provided } => write!(
+                f,
+                "The board grants the president {} here, but {} was provided.",
+                granted.description(),
+                provided.description()
+            ),
+            Error::GameAlreadyDecided => write!(
+                f,
+                "The fascists already won by policies, no further governments can be added."
+            ),
+            Error::BadGovernmentIndex(index) => write!(f, "Government #{index} does not exist."),
+            Error::BadExpression { column, found } => write!(
+                f,
+                "Failed to parse the fact expression, did not expect {found} at column {column}."
+            ),
+            Error::ParseGovernmentIndex(found) => write!(
+                f,
+                "Failed to parse the government, expected its number or \"next\", found {found} \
+                 instead."
+            ),
+            Error::BadConfidence(found) => write!(
+                f,
+                "Failed to parse the confidence, expected a percentage between 1 and 99, found \
+                 {found} instead."
+            ),
+            Error::SelfTargetedPower(action) => write!(
+                f,
+                "The president can't be the target of {} themselves.",
+                action.description()
+            ),
+            Error::RepeatedInvestigation(target, pi) => write!(
+                f,
+                "Player {} has already been investigated and can't be investigated again.",
+                pi.format_name(*target)
+            ),
+            Error::UnexpectedPowerArgument { granted, argument } => write!(
+                f,
+                "The board grants {} here, which doesn't take the argument \"{argument}\".",
+                granted.description()
+            ),
+            Error::EmptyPlayerSet => write!(f, "The set of players can't be empty."),
+            Error::FascistCountExceedsPlayers { count, players } => write!(
+                f,
+                "Exactly {count} fascists can't be among only {players} players."
+            ),
+            Error::ObservedDeckExceedsCirculation {
+                observed,
+                circulation
+            } => write!(
+                f,
+                "The deck can't hold {observed} cards when only {circulation} cards aren't on the \
+                 board."
+            ),
+            Error::UnknownEnactedPolicy => write!(
+                f,
+                "The enacted policy can't be inferred from the claims, append it to the \
+                 chancellor claim, e.g. \"?:r\"."
+            ),
+            Error::DiscardNotDrawn(policy) => {
+                write!(f, "Can't discard a {policy} policy which wasn't drawn.")
+            },
+            Error::BadPerspective(reason) => write!(f, "Invalid perspective, {reason}."),
+            Error::BadReadIndex(index) => write!(f, "Read #{index} does not exist."),
+            Error::TooManyReads => write!(
+                f,
+                "Too many active reads to weigh the role assignments, disable some of them with \
+                 toggle_read."
+            ),
+            Error::UnknownBranch(name) => write!(f, "There is no branch called \"{name}\"."),
+            Error::BranchExists(name) => {
+                write!(f, "There already is a branch called \"{name}\".")
+            },
+            Error::NotAFact(text) => {
+                write!(f, "\"{text}\" does not describe a fact about the players.")
+            },
+            Error::BadStrategy { found, expected } => write!(
+                f,
+                "Failed to parse the strategy, expected {expected}, found {found} instead."
+            ),
+            Error::BadPresidentialPower(found) => write!(
+                f,
+                "Failed to parse the presidential power, expected kill or special_election, found \
+                 {found} instead."
+            ),
+            Error::LogImportFailed(report) => {
+                write!(
+                    f,
+                    "Failed to import the log, nothing was changed:\n{report}"
+                )
+            }
         }
     }
 }
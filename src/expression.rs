@@ -0,0 +1,449 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt, str
+};
+
+use crate::{
+    error::{Error, Result},
+    players::{PlayerInfos, PlayerManager},
+    secret_role::SecretRole,
+    PlayerID
+};
+
+/// What an atom of an expression claims about a player.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Claim {
+    Liberal,
+    /// on the fascist team, Hitler included
+    Fascist,
+    Hitler
+}
+
+impl Claim {
+    pub(crate) fn holds(&self, role : SecretRole) -> bool {
+        match self {
+            Claim::Liberal => !role.is_fascist(),
+            Claim::Fascist => role.is_fascist(),
+            Claim::Hitler => role == SecretRole::Hitler
+        }
+    }
+}
+
+impl fmt::Display for Claim {
+    fn fmt(&self, f : &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Claim::Liberal => write!(f, "lib"),
+            Claim::Fascist => write!(f, "fasc"),
+            Claim::Hitler => write!(f, "hitler")
+        }
+    }
+}
+
+impl str::FromStr for Claim {
+    type Err = Error;
+
+    fn from_str(s : &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "l" | "b" | "lib" | "liberal" => Ok(Claim::Liberal),
+            "f" | "fasc" | "fascist" => Ok(Claim::Fascist),
+            "h" | "hitler" => Ok(Claim::Hitler),
+            _ => Err(Error::ParseRoleError(s.to_owned()))
+        }
+    }
+}
+
+/// A boolean expression over the roles of the players, e.g.
+/// `(f3 | f5) & !h4` or `lib(2) -> fasc(6)`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Expression {
+    Is(PlayerID, Claim),
+    Not(Box<Expression>),
+    And(Box<Expression>, Box<Expression>),
+    Or(Box<Expression>, Box<Expression>),
+    Implies(Box<Expression>, Box<Expression>),
+    Equivalent(Box<Expression>, Box<Expression>)
+}
+
+impl Expression {
+    pub(crate) fn evaluate(&self, roles : &BTreeMap<PlayerID, SecretRole>) -> Result<bool> {
+        Ok(match self {
+            Expression::Is(pid, claim) => {
+                claim.holds(*roles.get(pid).ok_or(Error::BadPlayerID(*pid))?)
+            },
+            Expression::Not(inner) => !inner.evaluate(roles)?,
+            Expression::And(left, right) => left.evaluate(roles)? && right.evaluate(roles)?,
+            Expression::Or(left, right) => left.evaluate(roles)? || right.evaluate(roles)?,
+            Expression::Implies(left, right) => !left.evaluate(roles)? || right.evaluate(roles)?,
+            Expression::Equivalent(left, right) => left.evaluate(roles)? == right.evaluate(roles)?
+        })
+    }
+
+    /// All players the expression talks about.
+    pub(crate) fn players(&self) -> BTreeSet<PlayerID> {
+        match self {
+            Expression::Is(pid, _) => [*pid].into(),
+            Expression::Not(inner) => inner.players(),
+            Expression::And(left, right)
+            | Expression::Or(left, right)
+            | Expression::Implies(left, right)
+            | Expression::Equivalent(left, right) => {
+                left.players().union(&right.players()).copied().collect()
+            },
+        }
+    }
+
+    /// Writes the expression back in the syntax it was parsed from.
+    pub(crate) fn format(&self, player_info : &PlayerInfos) -> String {
+        let operand = |inner : &Expression| match inner {
+            Expression::Is(..) | Expression::Not(_) => inner.format(player_info),
+            _ => format!("({})", inner.format(player_info))
+        };
+        match self {
+            Expression::Is(pid, claim) => format!("{claim}({})", player_info.format_name(*pid)),
+            Expression::Not(inner) => format!("!{}", operand(inner)),
+            Expression::And(left, right) => format!("{} & {}", operand(left), operand(right)),
+            Expression::Or(left, right) => format!("{} | {}", operand(left), operand(right)),
+            Expression::Implies(left, right) => {
+                format!("{} -> {}", operand(left), operand(right))
+            },
+            Expression::Equivalent(left, right) => {
+                format!("{} <-> {}", operand(left), operand(right))
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Token<'a> {
+    Word(&'a str),
+    Open,
+    Close,
+    Not,
+    And,
+    Or,
+    Implies,
+    Equivalent
+}
+
+/// Splits the input into tokens together with their (1-based) column.
+fn tokenize(input : &str) -> Result<Vec<(Token<'_>, usize)>> {
+    let mut tokens = vec![];
+    // columns count characters while slicing needs byte indices
+    let mut rest = input.char_indices().enumerate().peekable();
+    while let Some((column, (index, c))) = rest.next() {
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '(' => Token::Open,
+            ')' => Token::Close,
+            '!' => Token::Not,
+            '&' => Token::And,
+            '|' => Token::Or,
+            '-' if input[index..].starts_with("->") => {
+                rest.next();
+                Token::Implies
+            },
+            '<' if input[index..].starts_with("<->") => {
+                rest.nth(1);
+                Token::Equivalent
+            },
+            c if c.is_alphanumeric() || c == '_' => {
+                let mut end = index + c.len_utf8();
+                while let Some((_, (next, c))) = rest.peek().copied() {
+                    if !(c.is_alphanumeric() || c == '_') {
+                        break;
+                    }
+                    end = next + c.len_utf8();
+                    rest.next();
+                }
+                Token::Word(&input[index..end])
+            },
+            _ => {
+                return Err(Error::BadExpression {
+                    column : column + 1,
+                    found : c.to_string()
+                })
+            },
+        };
+        tokens.push((token, column + 1));
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a, 'b> {
+    tokens : Vec<(Token<'a>, usize)>,
+    position : usize,
+    end_column : usize,
+    player : &'b dyn Fn(&str) -> Result<PlayerID>
+}
+
+impl<'a> Parser<'a, '_> {
+    fn peek(&self) -> Option<Token<'a>> { self.tokens.get(self.position).map(|(token, _)| *token) }
+
+    fn unexpected(&self) -> Error {
+        match self.tokens.get(self.position) {
+            Some((token, column)) => Error::BadExpression {
+                column : *column,
+                found : match token {
+                    Token::Word(word) => word.to_string(),
+                    Token::Open => "(".to_owned(),
+                    Token::Close => ")".to_owned(),
+                    Token::Not => "!".to_owned(),
+                    Token::And => "&".to_owned(),
+                    Token::Or => "|".to_owned(),
+                    Token::Implies => "->".to_owned(),
+                    Token::Equivalent => "<->".to_owned()
+                }
+            },
+            None => Error::BadExpression {
+                column : self.end_column,
+                found : "the end of the expression".to_owned()
+            }
+        }
+    }
+
+    fn expect(&mut self, expected : Token<'_>) -> Result<()> {
+        if self.peek() == Some(expected) {
+            self.position += 1;
+            Ok(())
+        }
+        else {
+            Err(self.unexpected())
+        }
+    }
+
+    fn equivalence(&mut self) -> Result<Expression> {
+        let mut left = self.implication()?;
+        while self.peek() == Some(Token::Equivalent) {
+            self.position += 1;
+            left = Expression::Equivalent(Box::new(left), Box::new(self.implication()?));
+        }
+        Ok(left)
+    }
+
+    fn implication(&mut self) -> Result<Expression> {
+        let left = self.disjunction()?;
+        if self.peek() == Some(Token::Implies) {
+            self.position += 1;
+            return Ok(Expression::Implies(
+                Box::new(left),
+                Box::new(self.implication()?)
+            ));
+        }
+        Ok(left)
+    }
+
+    fn disjunction(&mut self) -> Result<Expression> {
+        let mut left = self.conjunction()?;
+        while self.peek() == Some(Token::Or) {
+            self.position += 1;
+            left = Expression::Or(Box::new(left), Box::new(self.conjunction()?));
+        }
+        Ok(left)
+    }
+
+    fn conjunction(&mut self) -> Result<Expression> {
+        let mut left = self.unary()?;
+        while self.peek() == Some(Token::And) {
+            self.position += 1;
+            left = Expression::And(Box::new(left), Box::new(self.unary()?));
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Expression> {
+        match self.peek() {
+            Some(Token::Not) => {
+                self.position += 1;
+                Ok(Expression::Not(Box::new(self.unary()?)))
+            },
+            Some(Token::Open) => {
+                self.position += 1;
+                let inner = self.equivalence()?;
+                self.expect(Token::Close)?;
+                Ok(inner)
+            },
+            Some(Token::Word(word)) => self.atom(word),
+            _ => Err(self.unexpected())
+        }
+    }
+
+    /// Either `lib(<player>)` or the short form `l<seat>`, likewise for
+    /// `fasc`/`f` and `hitler`/`h`.
+    fn atom(&mut self, word : &str) -> Result<Expression> {
+        let claim = |name : &str| name.parse::<Claim>().ok();
+
+        if self.tokens.get(self.position + 1).map(|(token, _)| *token) == Some(Token::Open) {
+            let claim = claim(word).ok_or_else(|| self.unexpected())?;
+            self.position += 2;
+            let Some(Token::Word(name)) = self.peek()
+            else {
+                return Err(self.unexpected());
+            };
+            let pid = (self.player)(name)?;
+            self.position += 1;
+            self.expect(Token::Close)?;
+            return Ok(Expression::Is(pid, claim));
+        }
+
+        let split = word
+            .find(|c : char| c.is_ascii_digit())
+            .ok_or_else(|| self.unexpected())?;
+        let (name, seat) = word.split_at(split);
+        let claim = claim(name).ok_or_else(|| self.unexpected())?;
+        let pid = (self.player)(seat)?;
+        self.position += 1;
+        Ok(Expression::Is(pid, claim))
+    }
+}
+
+/// Parses an expression, resolving player references with `player`.
+pub(crate) fn parse_expression(
+    input : &str,
+    player : &dyn Fn(&str) -> Result<PlayerID>
+) -> Result<Expression> {
+    let mut parser = Parser {
+        tokens : tokenize(input)?,
+        position : 0,
+        end_column : input.chars().count() + 1,
+        player
+    };
+    let expression = parser.equivalence()?;
+    if parser.position < parser.tokens.len() {
+        return Err(parser.unexpected());
+    }
+    Ok(expression)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn player(name : &str) -> Result<PlayerID> {
+        match name {
+            "jürgen" => Ok(9),
+            name => name
+                .parse()
+                .map_err(|_| Error::ParseNameError(name.to_owned()))
+        }
+    }
+
+    fn parse(input : &str) -> Result<Expression> { parse_expression(input, &player) }
+
+    fn is(pid : PlayerID, claim : Claim) -> Box<Expression> { Box::new(Expression::Is(pid, claim)) }
+
+    fn column(input : &str) -> (usize, String) {
+        match parse(input) {
+            Err(Error::BadExpression { column, found }) => (column, found),
+            other => panic!("expected a parse error, got {other:?}")
+        }
+    }
+
+    #[test]
+    fn atoms() {
+        assert_eq!(parse("f3").unwrap(), *is(3, Claim::Fascist));
+        assert_eq!(parse("lib(2)").unwrap(), *is(2, Claim::Liberal));
+        assert_eq!(parse("hitler ( jürgen )").unwrap(), *is(9, Claim::Hitler));
+        assert_eq!(parse("B10").unwrap(), *is(10, Claim::Liberal));
+    }
+
+    #[test]
+    fn precedence() {
+        assert_eq!(
+            parse("!l1 & f2 | h3").unwrap(),
+            Expression::Or(
+                Box::new(Expression::And(
+                    Box::new(Expression::Not(is(1, Claim::Liberal))),
+                    is(2, Claim::Fascist)
+                )),
+                is(3, Claim::Hitler)
+            )
+        );
+        assert_eq!(
+            parse("l1 | f2 -> h3 <-> l4").unwrap(),
+            Expression::Equivalent(
+                Box::new(Expression::Implies(
+                    Box::new(Expression::Or(is(1, Claim::Liberal), is(2, Claim::Fascist))),
+                    is(3, Claim::Hitler)
+                )),
+                is(4, Claim::Liberal)
+            )
+        );
+        assert_eq!(
+            parse("l1 & (f2 | h3)").unwrap(),
+            Expression::And(
+                is(1, Claim::Liberal),
+                Box::new(Expression::Or(is(2, Claim::Fascist), is(3, Claim::Hitler)))
+            )
+        );
+    }
+
+    #[test]
+    fn associativity() {
+        assert_eq!(
+            parse("l1 & l2 & l3").unwrap(),
+            Expression::And(
+                Box::new(Expression::And(
+                    is(1, Claim::Liberal),
+                    is(2, Claim::Liberal)
+                )),
+                is(3, Claim::Liberal)
+            )
+        );
+        assert_eq!(
+            parse("l1 -> l2 -> l3").unwrap(),
+            Expression::Implies(
+                is(1, Claim::Liberal),
+                Box::new(Expression::Implies(
+                    is(2, Claim::Liberal),
+                    is(3, Claim::Liberal)
+                ))
+            )
+        );
+        assert_eq!(
+            parse("l1 <-> l2 <-> l3").unwrap(),
+            Expression::Equivalent(
+                Box::new(Expression::Equivalent(
+                    is(1, Claim::Liberal),
+                    is(2, Claim::Liberal)
+                )),
+                is(3, Claim::Liberal)
+            )
+        );
+    }
+
+    #[test]
+    fn error_columns() {
+        assert_eq!(column("f3 $ f4"), (4, "$".to_owned()));
+        assert_eq!(column("f3 & "), (6, "the end of the expression".to_owned()));
+        assert_eq!(
+            column("(f3 | f4"),
+            (9, "the end of the expression".to_owned())
+        );
+        assert_eq!(column("f3 f4"), (4, "f4".to_owned()));
+        assert_eq!(column("x3"), (1, "x3".to_owned()));
+        assert_eq!(column("hitler(jürgen) & ?"), (18, "?".to_owned()));
+        assert_eq!(
+            column("hitler(jürgen) &"),
+            (17, "the end of the expression".to_owned())
+        );
+    }
+
+    #[test]
+    fn evaluation() {
+        let roles = BTreeMap::from([
+            (1, SecretRole::Liberal),
+            (2, SecretRole::RegularFascist),
+            (3, SecretRole::Hitler)
+        ]);
+        let holds = |input| parse(input).unwrap().evaluate(&roles).unwrap();
+        assert!(holds("l1 & f2 & f3 & h3"));
+        assert!(!holds("h2 | f1"));
+        assert!(holds("f1 -> h2"));
+        assert!(holds("f2 <-> !l2"));
+        assert!(matches!(
+            parse("l4").unwrap().evaluate(&roles),
+            Err(Error::BadPlayerID(4))
+        ));
+        assert_eq!(parse("l1 -> f2 | h3").unwrap().players(), [1, 2, 3].into());
+    }
+}
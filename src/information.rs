@@ -1,12 +1,96 @@
+use std::fmt;
+
 use itertools::Itertools;
 
 use crate::{
+    expression::{Claim, Expression},
     players::{PlayerFormatable, PlayerInfos},
     secret_role::SecretRole,
     PlayerID, PlayerManager
 };
 
-#[derive(Clone, Debug)]
+/// The rule turning the events of a single government into information.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Derivation {
+    /// the chancellor got elected after the third fascist policy
+    HitlerZoneElection,
+    PolicyConflict,
+    Execution,
+    Investigation,
+    PartyReveal,
+    /// a claim that can't be true given the policy that got enacted
+    EnactedPolicy
+}
+
+impl fmt::Display for Derivation {
+    fn fmt(&self, f : &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Derivation::HitlerZoneElection => write!(f, "chancellor elected in the Hitler zone"),
+            Derivation::PolicyConflict => write!(f, "policy conflict"),
+            Derivation::Execution => write!(f, "execution"),
+            Derivation::Investigation => write!(f, "investigation"),
+            Derivation::PartyReveal => write!(f, "party reveal"),
+            Derivation::EnactedPolicy => write!(f, "claim contradicting the enacted policy")
+        }
+    }
+}
+
+/// Where a piece of information was taken from, all indices are 0-based.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Origin {
+    ManualFact(usize),
+    Government(usize, Derivation),
+    /// a peek compared with the claim of the president drawing the cards
+    Peek {
+        peeking : usize,
+        drawing : usize
+    },
+    /// the claims made within a shuffle
+    Shuffle(usize)
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f : &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Origin::ManualFact(index) => write!(f, "manual fact #{}", index + 1),
+            Origin::Government(index, derivation) => {
+                write!(f, "government #{} ({derivation})", index + 1)
+            },
+            Origin::Peek { peeking, drawing } => write!(
+                f,
+                "the peek in government #{} and the claim in government #{}",
+                peeking + 1,
+                drawing + 1
+            ),
+            Origin::Shuffle(index) => write!(f, "the claims in shuffle #{}", index + 1)
+        }
+    }
+}
+
+impl Origin {
+    /// Adjusts the origin to a government being inserted or deleted at the
+    /// given index, `None` if the origin got deleted.
+    pub(crate) fn shift_government(self, government : usize, inserted : bool) -> Option<Self> {
+        let shift = |index : usize| match index {
+            index if index < government => Some(index),
+            index if inserted => Some(index + 1),
+            index if index == government => None,
+            index => Some(index - 1)
+        };
+        match self {
+            Origin::Government(index, derivation) => {
+                shift(index).map(|index| Origin::Government(index, derivation))
+            },
+            Origin::Peek { peeking, drawing } => Some(Origin::Peek {
+                peeking : shift(peeking)?,
+                drawing : shift(drawing)?
+            }),
+            origin => Some(origin)
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub(crate) enum Information {
     ConfirmedNotHitler(PlayerID),
     PolicyConflict(PlayerID, PlayerID),
@@ -19,7 +103,16 @@ pub(crate) enum Information {
         investigatee : PlayerID
     },
     HardFact(PlayerID, SecretRole),
-    AtLeastOneFascist(Vec<PlayerID>)
+    AtLeastOneFascist(Vec<PlayerID>),
+    SameTeam(PlayerID, PlayerID),
+    OppositeTeams(PlayerID, PlayerID),
+    /// Hitler counts as a fascist
+    ExactlyKFascists(Vec<PlayerID>, usize),
+    AtMostKFascists(Vec<PlayerID>, usize),
+    HitlerAmong(Vec<PlayerID>),
+    Custom(Expression),
+    /// the player lied about their cards, so they have to be a fascist
+    ContradictedClaim(PlayerID)
 }
 
 impl PlayerFormatable for Information {
@@ -60,11 +153,92 @@ impl PlayerFormatable for Information {
             ),
             Information::AtLeastOneFascist(suspicious_players) => format!(
                 "At least one of {} is a confirmed fascist.",
-                suspicious_players
-                    .iter()
-                    .map(|pid| format!("Player {}", player_info.format_name(*pid)))
-                    .join(", ")
+                format_players(suspicious_players, player_info)
+            ),
+            Information::SameTeam(left, right) => format!(
+                "Players {} and {} are on the same team.",
+                player_info.format_name(*left),
+                player_info.format_name(*right)
+            ),
+            Information::OppositeTeams(left, right) => format!(
+                "Players {} and {} are on opposite teams.",
+                player_info.format_name(*left),
+                player_info.format_name(*right)
+            ),
+            Information::ExactlyKFascists(players, count) => format!(
+                "Exactly {count} of {} are fascists.",
+                format_players(players, player_info)
+            ),
+            Information::AtMostKFascists(players, count) => format!(
+                "At most {count} of {} are fascists.",
+                format_players(players, player_info)
+            ),
+            Information::HitlerAmong(players) => {
+                format!("Hitler is one of {}.", format_players(players, player_info))
+            },
+            Information::Custom(expression) => {
+                format!("It holds that {}.", expression.format(player_info))
+            },
+            Information::ContradictedClaim(pid) => format!(
+                "Player {} made a claim contradicting the enacted policy and is a fascist.",
+                player_info.format_name(*pid)
             )
         }
     }
 }
+
+/// A behavioural read that makes role assignments agreeing with it more
+/// likely instead of ruling out the others.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct SoftRead {
+    pub player : PlayerID,
+    pub claim : Claim,
+    /// in percent, strictly between 0 and 100
+    pub confidence : usize,
+    pub enabled : bool
+}
+
+impl SoftRead {
+    /// The relative weight of a role assignment with `role` for the player.
+    pub(crate) fn weight(&self, role : SecretRole) -> usize {
+        let (agreeing, disagreeing) = (self.confidence, 100 - self.confidence);
+        let common = gcd(agreeing, disagreeing);
+        if self.claim.holds(role) {
+            agreeing / common
+        }
+        else {
+            disagreeing / common
+        }
+    }
+}
+
+fn gcd(left : usize, right : usize) -> usize {
+    if right == 0 {
+        left
+    }
+    else {
+        gcd(right, left % right)
+    }
+}
+
+impl PlayerFormatable for SoftRead {
+    fn format(&self, player_info : &PlayerInfos) -> String {
+        format!(
+            "Player {} reads as {} with {}% confidence.",
+            player_info.format_name(self.player),
+            match self.claim {
+                Claim::Liberal => "liberal",
+                Claim::Fascist => "fascist",
+                Claim::Hitler => "Hitler"
+            },
+            self.confidence
+        )
+    }
+}
+
+fn format_players(players : &[PlayerID], player_info : &PlayerInfos) -> String {
+    players
+        .iter()
+        .map(|pid| format!("Player {}", player_info.format_name(*pid)))
+        .join(", ")
+}
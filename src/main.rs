@@ -4,6 +4,7 @@ use repl_rs::{Command, Parameter, Repl, Value};
 
 mod deck;
 mod error;
+mod expression;
 mod information;
 mod players;
 mod policy;
@@ -17,7 +18,9 @@ use players::{game_configuration::GameConfiguration, *};
 
 #[derive(Debug)]
 pub struct Context {
-    player_state : PlayerState
+    player_state : PlayerState,
+    history : History,
+    branches : Branches
 }
 
 impl Context {
@@ -34,7 +37,9 @@ const VERSION : &str = env!("CARGO_PKG_VERSION");
 
 fn main() -> Result<(), Error> {
     Ok(Repl::new(Context {
-        player_state : PlayerState::new(GameConfiguration::new_standard(7, false)?)
+        player_state : PlayerState::new(GameConfiguration::new_standard(7, false)?),
+        history : Default::default(),
+        branches : Default::default()
     })
     .use_completion(true)
     .with_description("Tool to assist with computational secret hitler questions.")
@@ -71,6 +76,35 @@ fn main() -> Result<(), Error> {
                  for a deck with the specified amount of liberal and fascist cards."
             )
     )
+    .add_command(
+        Command::new("next_gov", next_gov)
+            .with_parameter(Parameter::new("pattern").set_required(true)?)?
+            .with_parameter(Parameter::new("truthful").set_default("false")?)?
+            .with_help(
+                "Like next, but uses the deck of the tracked game. If \"truthful\" is set to \
+                 true, all claims since the last shuffle are assumed to be true."
+            )
+    )
+    .add_command(
+        Command::new("dist_gov", dist_gov)
+            .with_parameter(Parameter::new("window_size").set_default("3")?)?
+            .with_parameter(Parameter::new("truthful").set_default("false")?)?
+            .with_help(
+                "Like dist, but uses the deck of the tracked game (default window is the next \
+                 three cards). If \"truthful\" is set to true, all claims since the last shuffle \
+                 are assumed to be true."
+            )
+    )
+    .add_command(
+        Command::new("piles", piles)
+            .with_parameter(Parameter::new("allow_fascist_fascist_conflict").set_required(true)?)?
+            .with_parameter(Parameter::new("allow_aggressive_hitler").set_required(true)?)?
+            .with_help(
+                "Computes the distribution of liberal policies in the draw pile and discard pile \
+                 of the tracked game, assuming liberals told the truth about what they drew since \
+                 the last shuffle."
+            )
+    )
     .add_command(
         Command::new("standard_game", standard_game)
             .with_parameter(Parameter::new("player_count").set_required(true)?)?
@@ -101,15 +135,95 @@ fn main() -> Result<(), Error> {
         Command::new("show_manual_facts", show_facts)
             .with_help("Shows the manually added facts with indices for removal.")
     )
-    .add_command(
-        Command::new("known_facts", show_known_facts)
-            .with_help("Shows all the information deduced about this game.")
-    )
+    .add_command(Command::new("known_facts", show_known_facts).with_help(
+        "Shows all the information deduced about this game together with where it was taken from."
+    ))
     .add_command(
         Command::new("remove_fact", remove_fact)
             .with_parameter(Parameter::new("fact_to_be_removed").set_required(true)?)?
             .with_help("Removes the fact with the given index from the database.")
     )
+    .add_command(
+        Command::new("disable_fact", disable_fact)
+            .with_parameter(Parameter::new("known_fact").set_required(true)?)?
+            .with_help(
+                "Excludes the manual or deduced fact with the given index in known_facts from all \
+                 analyses."
+            )
+    )
+    .add_command(
+        Command::new("enable_fact", enable_fact)
+            .with_parameter(Parameter::new("known_fact").set_required(true)?)?
+            .with_help("Includes the disabled fact with the given index in known_facts again.")
+    )
+    .add_command(
+        Command::new("upcoming", upcoming)
+            .with_parameter(Parameter::new("allow_fascist_fascist_conflict").set_required(true)?)?
+            .with_parameter(Parameter::new("allow_aggressive_hitler").set_required(true)?)?
+            .with_parameter(
+                Parameter::new("count")
+                    .set_required(false)?
+                    .set_default("5")?
+            )?
+            .with_help(
+                "Shows the next presidents if every government gets elected and the eligible \
+                 chancellors of the next president with their chances of being a fascist or \
+                 Hitler."
+            )
+    )
+    .add_command(
+        Command::new("whoami", whoami)
+            .with_parameter(Parameter::new("seat").set_required(true)?)?
+            .with_parameter(Parameter::new("role").set_required(true)?)?
+            .with_parameter(
+                Parameter::new("teammates")
+                    .set_required(false)?
+                    .set_default("")?
+            )?
+            .with_help(
+                "Analyses the game from your own seat and secret role. As a fascist (or as Hitler \
+                 in games of up to 6 players), list all teammates comma-separated and mark Hitler \
+                 with :h, e.g. 5,7:h."
+            )
+    )
+    .add_command(
+        Command::new("plan_claim", plan_claim)
+            .with_parameter(Parameter::new("chancellor").set_required(true)?)?
+            .with_parameter(Parameter::new("drawn").set_required(true)?)?
+            .with_parameter(Parameter::new("discarded").set_required(true)?)?
+            .with_parameter(Parameter::new("allow_fascist_fascist_conflict").set_required(true)?)?
+            .with_parameter(Parameter::new("allow_aggressive_hitler").set_required(true)?)?
+            .with_help(
+                "As a fascist president set up with whoami, rates every claim about the drawn \
+                 policies by how believable it is, how likely the chancellor contradicts it and \
+                 how it shifts the public view of each known fascist."
+            )
+    )
+    .add_command(
+        Command::new("spectator", spectator)
+            .with_help("Drops the perspective set by whoami and analyses as a spectator again.")
+    )
+    .add_command(
+        Command::new("read", add_soft_read)
+            .with_parameter(Parameter::new("player").set_required(true)?)?
+            .with_parameter(Parameter::new("role").set_required(true)?)?
+            .with_parameter(Parameter::new("confidence").set_required(true)?)?
+            .with_help(
+                "Adds a soft read that the player is liberal, fascist or Hitler with the given \
+                 confidence in percent, which weighs the probabilities instead of ruling out \
+                 assignments."
+            )
+    )
+    .add_command(
+        Command::new("toggle_read", toggle_read)
+            .with_parameter(Parameter::new("read").set_required(true)?)?
+            .with_help("Disables or re-enables the soft read with the given index.")
+    )
+    .add_command(
+        Command::new("remove_read", remove_read)
+            .with_parameter(Parameter::new("read").set_required(true)?)?
+            .with_help("Removes the soft read with the given index.")
+    )
     .add_command(
         Command::new("conflict", add_conflict)
             .with_parameter(Parameter::new("president").set_required(true)?)?
@@ -119,6 +233,51 @@ fn main() -> Result<(), Error> {
                  database."
             )
     )
+    .add_command(
+        Command::new("same_team", same_team)
+            .with_parameter(Parameter::new("first").set_required(true)?)?
+            .with_parameter(Parameter::new("second").set_required(true)?)?
+            .with_help("Adds that both players are on the same team to the fact database.")
+    )
+    .add_command(
+        Command::new("opposite_teams", opposite_teams)
+            .with_parameter(Parameter::new("first").set_required(true)?)?
+            .with_parameter(Parameter::new("second").set_required(true)?)?
+            .with_help("Adds that the players are on opposite teams to the fact database.")
+    )
+    .add_command(
+        Command::new("exactly_fascists", exactly_fascists)
+            .with_parameter(Parameter::new("count").set_required(true)?)?
+            .with_parameter(Parameter::new("players").set_required(true)?)?
+            .with_help(
+                "Adds that exactly count of the comma-separated players are fascists (including \
+                 Hitler) to the fact database."
+            )
+    )
+    .add_command(
+        Command::new("at_most_fascists", at_most_fascists)
+            .with_parameter(Parameter::new("count").set_required(true)?)?
+            .with_parameter(Parameter::new("players").set_required(true)?)?
+            .with_help(
+                "Adds that at most count of the comma-separated players are fascists (including \
+                 Hitler) to the fact database."
+            )
+    )
+    .add_command(
+        Command::new("hitler_among", hitler_among)
+            .with_parameter(Parameter::new("players").set_required(true)?)?
+            .with_help(
+                "Adds that Hitler is one of the comma-separated players to the fact database."
+            )
+    )
+    .add_command(
+        Command::new("fact", add_custom_fact)
+            .with_parameter(Parameter::new("expression").set_required(true)?)?
+            .with_help(
+                "Adds a quoted boolean expression over the players' roles to the fact database, \
+                 e.g. \"(f3 | f5) & !h4\" or \"lib(2) -> fasc(6)\"."
+            )
+    )
     .add_command(
         Command::new("confirm_not_hitler", confirm_not_hitler)
             .with_parameter(Parameter::new("player").set_required(true)?)?
@@ -160,6 +319,63 @@ fn main() -> Result<(), Error> {
                  information."
             )
     )
+    .add_command(
+        Command::new("investigation_advice", investigation_advice)
+            .with_parameter(Parameter::new("investigator").set_required(true)?)?
+            .with_parameter(Parameter::new("allow_fascist_fascist_conflict").set_required(true)?)?
+            .with_parameter(Parameter::new("allow_aggressive_hitler").set_required(true)?)?
+            .with_help(
+                "Ranks the players the investigator could investigate by how much the result is \
+                 expected to reduce the uncertainty about everybody's role."
+            )
+    )
+    .add_command(
+        Command::new("advise", advise)
+            .with_parameter(Parameter::new("power").set_required(true)?)?
+            .with_parameter(Parameter::new("president").set_required(true)?)?
+            .with_parameter(Parameter::new("allow_fascist_fascist_conflict").set_required(true)?)?
+            .with_parameter(Parameter::new("allow_aggressive_hitler").set_required(true)?)?
+            .with_help(
+                "Ranks the alive players other than the president as targets for the power, which \
+                 is either kill or special_election, by their chance of being Hitler or liberal. \
+                 For kills it also shows how the remaining players look once the target is gone."
+            )
+    )
+    .add_command(
+        Command::new("win_odds", win_odds)
+            .with_parameter(Parameter::new("liberal_strategy").set_required(true)?)?
+            .with_parameter(Parameter::new("fascist_strategy").set_required(true)?)?
+            .with_parameter(Parameter::new("allow_fascist_fascist_conflict").set_required(true)?)?
+            .with_parameter(Parameter::new("allow_aggressive_hitler").set_required(true)?)?
+            .with_parameter(Parameter::new("games").set_default("10000")?)?
+            .with_help(
+                "Simulates the rest of the game with roles consistent with the known information \
+                 and reports the chances of either team winning. Liberals pick chancellors and \
+                 kill targets either random or informed (by the current probabilities), fascists \
+                 play either aggressive or cautious (only playing fascist policies in all-fascist \
+                 governments)."
+            )
+    )
+    .add_command(
+        Command::new("explain", explain)
+            .with_parameter(Parameter::new("player").set_required(true)?)?
+            .with_parameter(Parameter::new("role").set_required(true)?)?
+            .with_parameter(Parameter::new("allow_fascist_fascist_conflict").set_required(true)?)?
+            .with_parameter(Parameter::new("allow_aggressive_hitler").set_required(true)?)?
+            .with_help(
+                "Lists a minimal set of known facts which together rule out the player having the \
+                 given role, together with where each fact comes from."
+            )
+    )
+    .add_command(
+        Command::new("diagnose", diagnose)
+            .with_parameter(Parameter::new("allow_fascist_fascist_conflict").set_required(true)?)?
+            .with_parameter(Parameter::new("allow_aggressive_hitler").set_required(true)?)?
+            .with_help(
+                "Lists a minimal set of contradicting known facts and which single fact to \
+                 disable to restore consistency."
+            )
+    )
     .add_command(
         Command::new("liberal_percent", liberal_percent)
             .with_parameter(Parameter::new("allow_fascist_fascist_conflict").set_required(true)?)?
@@ -189,12 +405,56 @@ fn main() -> Result<(), Error> {
                  \"bash\"."
             )
     )
+    .add_command(
+        Command::new("whatif", whatif)
+            .with_parameter(Parameter::new("facts").set_required(true)?)?
+            .with_parameter(Parameter::new("command").set_required(true)?)?
+            .with_parameter(Parameter::new("first_argument").set_default("")?)?
+            .with_parameter(Parameter::new("second_argument").set_default("")?)?
+            .with_parameter(Parameter::new("third_argument").set_default("")?)?
+            .with_help(
+                "Runs hitler_snipe, liberal_percent, impossible_teams or graph with the given \
+                 arguments as if the quoted, comma- or semicolon-separated facts were known as \
+                 well, e.g. whatif \"4 is lib; 2 and 5 conflict\" hitler_snipe false false. The \
+                 stored facts are left untouched."
+            )
+    )
     .add_command(
         Command::new("name", name)
             .with_parameter(Parameter::new("position").set_required(true)?)?
             .with_parameter(Parameter::new("display_name").set_required(true)?)?
             .with_help("Names a player for nicer reading.")
     )
+    .add_command(
+        Command::new("branch", branch)
+            .with_parameter(Parameter::new("name").set_required(true)?)?
+            .with_help(
+                "Copies the whole tracked game (governments, facts and undo history) into a new \
+                 branch with the given name and switches to it."
+            )
+    )
+    .add_command(
+        Command::new("switch", switch)
+            .with_parameter(Parameter::new("name").set_required(true)?)?
+            .with_help(
+                "Switches to the branch with the given name, the initial branch is \"main\"."
+            )
+    )
+    .add_command(
+        Command::new("branches", branches)
+            .with_help("Lists all branches, the active one is marked with a star.")
+    )
+    .add_command(
+        Command::new("compare", compare)
+            .with_parameter(Parameter::new("left").set_required(true)?)?
+            .with_parameter(Parameter::new("right").set_required(true)?)?
+            .with_parameter(Parameter::new("allow_fascist_fascist_conflict").set_required(true)?)?
+            .with_parameter(Parameter::new("allow_aggressive_hitler").set_required(true)?)?
+            .with_help(
+                "Shows the chances of every player being a liberal and being Hitler in both \
+                 branches side by side."
+            )
+    )
     .add_command(
         Command::new("topdeck", topdeck)
             .with_parameter(Parameter::new("drawn_policy").set_required(true)?)?
@@ -211,13 +471,72 @@ fn main() -> Result<(), Error> {
             .with_help(
                 "Logs a government with president, chancellor and claims. Conflicts are detected \
                  by the president claiming a non-0 amount of blue policies and the chancellor \
-                 claiming 0. Conflicts are automatically registered for analysis."
+                 claiming 0 or the chancellor claiming more blue policies than the president \
+                 could have passed. Conflicts are automatically registered for analysis. The \
+                 enacted policy can be appended to the chancellor claim, e.g. `rb:r`, to detect \
+                 claims contradicting it. A claim that wasn't made or heard can be entered as `?`."
+            )
+    )
+    .add_command(
+        Command::new("import_log", import_log)
+            .with_parameter(Parameter::new("source").set_required(true)?)?
+            .with_help(
+                "Imports a game log in the shorthand typically typed into the chat, one event per \
+                 line, e.g. \"3>1 rrb/rb\", \"topdeck r\", \"4 inv 2 lib\", \"kill 5\", \"peek \
+                 rrb\", \"se 4\", \"burn r\", \"conflict 3 1\", \"2 is lib\" or \"6 not hitler\". \
+                 Actions on their own line belong to the government right before them. Lines \
+                 starting with \"#\" are ignored. The source is a filename or \"clipboard\" to \
+                 import the copied text. Nothing is changed if any line fails to import."
+            )
+    )
+    .add_command(
+        Command::new("edit_government", edit_government)
+            .with_parameter(Parameter::new("index").set_required(true)?)?
+            .with_parameter(Parameter::new("president").set_required(true)?)?
+            .with_parameter(Parameter::new("chancellor").set_required(true)?)?
+            .with_parameter(Parameter::new("presidential_blues").set_required(true)?)?
+            .with_parameter(Parameter::new("chancellor_blues").set_required(true)?)?
+            .with_parameter(Parameter::new("first_argument").set_default("NULL")?)?
+            .with_parameter(Parameter::new("second_argument").set_default("NULL")?)?
+            .with_help(
+                "Replaces the government with the given index (as shown by show_governments) \
+                 using the same arguments as the government command. All later entries are \
+                 re-evaluated and the ones that became invalid are reported."
+            )
+    )
+    .add_command(
+        Command::new("insert_government", insert_government)
+            .with_parameter(Parameter::new("index").set_required(true)?)?
+            .with_parameter(Parameter::new("president").set_required(true)?)?
+            .with_parameter(Parameter::new("chancellor").set_required(true)?)?
+            .with_parameter(Parameter::new("presidential_blues").set_required(true)?)?
+            .with_parameter(Parameter::new("chancellor_blues").set_required(true)?)?
+            .with_parameter(Parameter::new("first_argument").set_default("NULL")?)?
+            .with_parameter(Parameter::new("second_argument").set_default("NULL")?)?
+            .with_help(
+                "Inserts a government at the given index (as shown by show_governments) using the \
+                 same arguments as the government command. All later entries are re-evaluated and \
+                 the ones that became invalid are reported."
+            )
+    )
+    .add_command(
+        Command::new("delete_government", delete_government)
+            .with_parameter(Parameter::new("index").set_required(true)?)?
+            .with_help(
+                "Removes the government with the given index (as shown by show_governments). All \
+                 later entries are re-evaluated and the ones that became invalid are reported."
             )
     )
     .add_command(
         Command::new("pop_government", pop_government)
             .with_help("Removes the latest government from the state.")
     )
+    .add_command(Command::new("undo", undo).with_help(
+        "Reverts the latest change to the tracked game, e.g. an added government, fact or name."
+    ))
+    .add_command(
+        Command::new("redo", redo).with_help("Re-applies the latest change reverted by undo.")
+    )
     .add_command(
         Command::new("show_governments", show_governments)
             .with_help("Shows the currently registered governments.")
@@ -236,6 +555,27 @@ fn main() -> Result<(), Error> {
                  configuration."
             )
     )
+    .add_command(
+        Command::new("deck_count", deck_count)
+            .with_parameter(Parameter::new("observed_cards").set_required(true)?)?
+            .with_parameter(Parameter::new("correct").set_default("false")?)?
+            .with_parameter(Parameter::new("before_government").set_default("next")?)?
+            .with_help(
+                "Compares the observed number of cards in the deck with the tracked deck size \
+                 before the given government (default: the next one). If \"correct\" is set to \
+                 true, the tracked deck is overridden with the observation and all later \
+                 governments and reshuffles are re-evaluated."
+            )
+    )
+    .add_command(Command::new("deck_timeline", deck_timeline).with_help(
+        "Shows the tracked deck size and discard pile size before each government, where the deck \
+         got reshuffled and which cards were claimed to be consumed."
+    ))
+    .add_command(Command::new("liars", liars).with_help(
+        "Computes for each shuffle the minimal groups of presidents and chancellors whose claims \
+         (including peeks) must have been lies for the shuffle to be consistent. The groups that \
+         can't all have told the truth are used as facts for the analysis."
+    ))
     .add_command(
         Command::new("shuffle_probabilities", total_draw_probability).with_help(
             "Computes the probability of the occured shuffles happening assuming nobody lied."
@@ -0,0 +1,216 @@
+use std::collections::{BTreeMap, HashMap};
+
+use itertools::Itertools;
+
+use crate::{
+    deck::FilterResult,
+    error::{Error, Result},
+    information::Information,
+    secret_role::SecretRole,
+    PlayerID
+};
+
+use super::{filter_engine::filtered_histogramm, iter_elected, PlayerState, PresidentialAction::*};
+
+pub(super) type RoleHistogram = BTreeMap<PlayerID, (HashMap<SecretRole, FilterResult>, usize)>;
+
+/// The sum of the entropies (in bits) of every player's role distribution.
+pub(super) fn histogram_entropy(histogram : &RoleHistogram) -> f64 {
+    histogram
+        .values()
+        .flat_map(|(roles, _total)| roles.values())
+        .map(FilterResult::probability)
+        .filter(|p| *p > 0.0)
+        .map(|p| -p * p.log2())
+        .sum()
+}
+
+/// How likely the player is to be either a regular fascist or hitler.
+pub(super) fn fascist_probability(histogram : &RoleHistogram, player : PlayerID) -> FilterResult {
+    histogram
+        .get(&player)
+        .map(|(roles, total)| FilterResult {
+            num_matching : roles
+                .iter()
+                .filter(|(role, _fr)| role.is_fascist())
+                .map(|(_role, fr)| fr.num_matching)
+                .sum(),
+            num_checked : *total
+        })
+        .unwrap_or(FilterResult::none(0))
+}
+
+pub(super) struct InvestigationRating {
+    pub investigatee : PlayerID,
+    pub liberal_result_probability : f64,
+    pub expected_entropy_reduction : f64
+}
+
+/// Rates every possible investigatee by how much uncertainty about the roles
+/// the investigation is expected to remove. The probability of each result is
+/// proportional to the number of role assignments consistent with it. A
+/// fascist investigator is assumed to claim either result equally likely, so
+/// the assignments with a fascist investigator count half towards each result.
+pub(super) fn rate_investigations(
+    filter_args : (bool, bool),
+    player_state : &PlayerState,
+    investigator : PlayerID
+) -> Result<(FilterResult, Vec<InvestigationRating>)> {
+    let histogram = filtered_histogramm(filter_args, player_state, &[])?;
+    let current_entropy = histogram_entropy(&histogram);
+
+    // the outcome of an impossible result doesn't matter as it never happens
+    let hypothetical = |information : Information| match filtered_histogramm(
+        filter_args,
+        player_state,
+        &[information]
+    ) {
+        Ok(histogram) => {
+            let total = histogram
+                .values()
+                .next()
+                .map_or(0, |(_roles, total)| *total);
+            let fascist_investigator = fascist_probability(&histogram, investigator).num_matching;
+            Ok((
+                total as f64 - fascist_investigator as f64 / 2.0,
+                histogram_entropy(&histogram)
+            ))
+        },
+        Err(Error::LogicalInconsistency) => Ok((0.0, 0.0)),
+        Err(e) => Err(e)
+    };
+
+    let already_investigated = iter_elected(&player_state.governments)
+        .filter_map(|gov| match gov.presidential_action {
+            Investigation(investigatee, _) => Some(investigatee),
+            _ => None
+        })
+        .collect_vec();
+
+    let ratings = player_state
+        .player_info
+        .keys()
+        .copied()
+        .filter(|investigatee| *investigatee != investigator)
+        .filter(|investigatee| !already_investigated.contains(investigatee))
+        .filter(|investigatee| {
+            player_state
+                .player_interactable(*investigatee, &player_state.player_info)
+                .is_ok()
+        })
+        .map(|investigatee| {
+            let (liberal_count, liberal_entropy) =
+                hypothetical(Information::LiberalInvestigation {
+                    investigator,
+                    investigatee
+                })?;
+            let (fascist_count, fascist_entropy) =
+                hypothetical(Information::FascistInvestigation {
+                    investigator,
+                    investigatee
+                })?;
+            let liberal_result_probability = if liberal_count + fascist_count > 0.0 {
+                liberal_count / (liberal_count + fascist_count)
+            }
+            else {
+                0.0
+            };
+            let expected_entropy = liberal_result_probability * liberal_entropy
+                + (1.0 - liberal_result_probability) * fascist_entropy;
+
+            Ok(InvestigationRating {
+                investigatee,
+                liberal_result_probability,
+                expected_entropy_reduction : current_entropy - expected_entropy
+            })
+        })
+        .collect::<Result<Vec<_>>>()?
+        .into_iter()
+        .sorted_by(|l, r| {
+            r.expected_entropy_reduction
+                .total_cmp(&l.expected_entropy_reduction)
+        })
+        .collect();
+
+    Ok((fascist_probability(&histogram, investigator), ratings))
+}
+
+pub(super) struct TargetRating {
+    pub target : PlayerID,
+    pub hitler : FilterResult,
+    pub liberal : FilterResult,
+    /// the expected share of liberals among the other alive players and the
+    /// uncertainty about their roles, given the target wasn't hitler,
+    /// `None` if the target is certainly hitler
+    pub after_removal : Option<(f64, f64)>
+}
+
+/// Rates every alive player other than the acting president as the target of
+/// a kill or special election.
+pub(super) fn rate_targets(
+    filter_args : (bool, bool),
+    player_state : &PlayerState,
+    president : PlayerID
+) -> Result<Vec<TargetRating>> {
+    let histogram = filtered_histogramm(filter_args, player_state, &[])?;
+    let alive = player_state
+        .player_info
+        .keys()
+        .copied()
+        .filter(|pid| {
+            player_state
+                .player_interactable(*pid, &player_state.player_info)
+                .is_ok()
+        })
+        .collect_vec();
+    let role_probability = |histogram : &RoleHistogram, pid : PlayerID, role : SecretRole| {
+        histogram
+            .get(&pid)
+            .map(|(roles, total)| {
+                roles
+                    .get(&role)
+                    .copied()
+                    .unwrap_or(FilterResult::none(*total))
+            })
+            .unwrap_or(FilterResult::none(0))
+    };
+
+    alive
+        .iter()
+        .copied()
+        .filter(|target| *target != president)
+        .map(|target| {
+            let after_removal = match filtered_histogramm(
+                filter_args,
+                player_state,
+                &[Information::ConfirmedNotHitler(target)]
+            ) {
+                Ok(remaining) => {
+                    let survivors = alive
+                        .iter()
+                        .filter(|pid| **pid != target)
+                        .map(|pid| (*pid, remaining[pid].clone()))
+                        .collect::<RoleHistogram>();
+                    let expected_liberals : f64 = survivors
+                        .keys()
+                        .map(|pid| role_probability(&survivors, *pid, SecretRole::Liberal))
+                        .map(|fr| fr.probability())
+                        .sum();
+                    Some((
+                        expected_liberals / survivors.len().max(1) as f64,
+                        histogram_entropy(&survivors)
+                    ))
+                },
+                Err(Error::LogicalInconsistency) => None,
+                Err(e) => return Err(e)
+            };
+
+            Ok(TargetRating {
+                target,
+                hitler : role_probability(&histogram, target, SecretRole::Hitler),
+                liberal : role_probability(&histogram, target, SecretRole::Liberal),
+                after_removal
+            })
+        })
+        .collect()
+}
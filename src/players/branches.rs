@@ -0,0 +1,94 @@
+use std::collections::BTreeMap;
+
+use crate::error::{Error, Result};
+
+use super::{History, PlayerState};
+
+/// Named alternative timelines of the tracked game. The active branch lives
+/// in the context, all others are stored here together with their history.
+#[derive(Debug)]
+pub(crate) struct Branches {
+    active : String,
+    stored : BTreeMap<String, (PlayerState, History)>
+}
+
+impl Default for Branches {
+    fn default() -> Self {
+        Self {
+            active : "main".to_owned(),
+            stored : Default::default()
+        }
+    }
+}
+
+impl Branches {
+    pub(super) fn active(&self) -> &str { &self.active }
+
+    /// All branch names in order together with whether they are active.
+    pub(super) fn names(&self) -> Vec<(&str, bool)> {
+        let mut names = self
+            .stored
+            .keys()
+            .map(|name| (name.as_str(), false))
+            .chain(std::iter::once((self.active.as_str(), true)))
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+
+    /// Looks up a branch, the active one has to be provided by the caller.
+    pub(super) fn get<'a>(
+        &'a self,
+        name : &str,
+        active : &'a PlayerState
+    ) -> Result<&'a PlayerState> {
+        if name == self.active {
+            Ok(active)
+        }
+        else {
+            self.stored
+                .get(name)
+                .map(|(player_state, _history)| player_state)
+                .ok_or_else(|| Error::UnknownBranch(name.to_owned()))
+        }
+    }
+
+    /// Stores a copy of the active branch under `name` and makes the copy the
+    /// active branch.
+    pub(super) fn fork(
+        &mut self,
+        name : &str,
+        player_state : &PlayerState,
+        history : &History
+    ) -> Result<()> {
+        if name == self.active || self.stored.contains_key(name) {
+            return Err(Error::BranchExists(name.to_owned()));
+        }
+        let previous = std::mem::replace(&mut self.active, name.to_owned());
+        self.stored
+            .insert(previous, (player_state.clone(), history.clone()));
+        Ok(())
+    }
+
+    /// Stores the active branch and activates the branch called `name`.
+    pub(super) fn switch(
+        &mut self,
+        name : &str,
+        player_state : &mut PlayerState,
+        history : &mut History
+    ) -> Result<()> {
+        if name == self.active {
+            return Ok(());
+        }
+        let (stored_state, stored_history) = self
+            .stored
+            .remove(name)
+            .ok_or_else(|| Error::UnknownBranch(name.to_owned()))?;
+        let previous = std::mem::replace(&mut self.active, name.to_owned());
+        let previous_state = player_state.restore(stored_state);
+        let previous_history = std::mem::replace(history, stored_history);
+        self.stored
+            .insert(previous, (previous_state, previous_history));
+        Ok(())
+    }
+}
@@ -32,6 +32,15 @@ impl<T> Default for CallBackVec<T> {
     }
 }
 
+impl<T : Clone> Clone for CallBackVec<T> {
+    fn clone(&self) -> Self {
+        Self {
+            data : self.data.clone(),
+            callbacks : self.callbacks.clone()
+        }
+    }
+}
+
 impl<T : fmt::Debug> fmt::Debug for CallBackVec<T> {
     fn fmt(&self, f : &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("CallBackVec")
@@ -69,6 +78,10 @@ impl<T> CallBackVec<T> {
         }
     }
 
+    /// Splits off all items starting at `index` without notifying anyone, for
+    /// staged modifications.
+    pub(crate) fn split_off(&mut self, index : usize) -> Vec<T> { self.data.split_off(index) }
+
     pub(crate) fn register_callback(
         &mut self,
         kind : CallbackKind,
@@ -78,4 +91,9 @@ impl<T> CallBackVec<T> {
     }
 
     pub(crate) fn callback(&self) -> Callback { self.generate_callbacks() }
+
+    /// Replaces the registered callbacks with the ones of `other`.
+    pub(crate) fn adopt_callbacks(&mut self, other : &Self) {
+        self.callbacks = other.callbacks.clone();
+    }
 }
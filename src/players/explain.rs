@@ -0,0 +1,102 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    information::{Information, Origin},
+    secret_role::SecretRole,
+    PlayerID
+};
+
+use super::{filter_engine::valid_role_assignments, PlayerState};
+
+/// Whether `information` leaves none of the `candidates`.
+fn rules_out(
+    (allow_fascist_fascist_conflict, allow_aggressive_hitler) : (bool, bool),
+    candidates : &[BTreeMap<PlayerID, SecretRole>],
+    information : &[(Information, Origin)]
+) -> bool {
+    let information = information
+        .iter()
+        .map(|(information, _origin)| information.clone())
+        .collect::<Vec<_>>();
+    !candidates.iter().any(|roles| {
+        valid_role_assignments(
+            roles,
+            &information,
+            !allow_aggressive_hitler,
+            !allow_fascist_fascist_conflict
+        )
+        .unwrap_or(false)
+    })
+}
+
+/// Shrinks `information` to a subset that still leaves no role assignment
+/// matching `candidate`, by dropping every entry that isn't needed for that.
+/// No entry of the result can be removed without allowing such an
+/// assignment again. `None` if even all of `information` allows one.
+pub(super) fn minimal_core(
+    filter_args : (bool, bool),
+    player_state : &PlayerState,
+    information : Vec<(Information, Origin)>,
+    candidate : impl Fn(&BTreeMap<PlayerID, SecretRole>) -> bool
+) -> Option<Vec<(Information, Origin)>> {
+    let candidates = player_state
+        .current_roles()
+        .into_iter()
+        .filter(|roles| candidate(roles))
+        .collect::<Vec<_>>();
+
+    if !rules_out(filter_args, &candidates, &information) {
+        return None;
+    }
+
+    let mut core = information;
+    let mut index = 0;
+    while index < core.len() {
+        let removed = core.remove(index);
+        if !rules_out(filter_args, &candidates, &core) {
+            core.insert(index, removed);
+            index += 1;
+        }
+    }
+
+    Some(core)
+}
+
+/// A contradiction within the active information.
+pub(super) struct Inconsistency {
+    /// a subset of the information that is already contradictory on its own
+    /// but becomes consistent when dropping any of its entries
+    pub core : Vec<(Information, Origin)>,
+    /// the entries of the core whose removal alone makes all of the
+    /// information consistent again
+    pub resolving : Vec<(Information, Origin)>
+}
+
+/// Finds out why the active information leaves no role assignment, `None`
+/// if it doesn't contradict itself.
+pub(super) fn diagnose_inconsistency(
+    filter_args : (bool, bool),
+    player_state : &PlayerState
+) -> Option<Inconsistency> {
+    let information = player_state.collect_information_with_origin();
+    let core = minimal_core(filter_args, player_state, information.clone(), |_roles| {
+        true
+    })?;
+
+    // every entry resolving the contradiction is part of each core
+    let candidates = player_state.current_roles();
+    let resolving = core
+        .iter()
+        .filter(|fact| {
+            let remaining = information
+                .iter()
+                .filter(|other| other != fact)
+                .cloned()
+                .collect::<Vec<_>>();
+            !rules_out(filter_args, &candidates, &remaining)
+        })
+        .cloned()
+        .collect();
+
+    Some(Inconsistency { core, resolving })
+}
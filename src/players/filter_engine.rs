@@ -27,6 +27,7 @@ fn no_aggressive_hitler_filter(
         Information::FascistInvestigation { investigator, .. } => {
             Ok(lp(investigator)? != &SecretRole::Hitler)
         },
+        Information::ContradictedClaim(pid) => Ok(lp(pid)? != &SecretRole::Hitler),
         _ => Ok(true)
     }
 }
@@ -66,15 +67,38 @@ fn universal_deducable_information(
             investigatee
         } => Ok(lp(investigator)?.is_fascist() || lp(investigatee)?.is_fascist()),
         Information::HardFact(pid, role) => Ok(lp(pid)? == role),
+        Information::ContradictedClaim(pid) => Ok(lp(pid)?.is_fascist()),
         Information::AtLeastOneFascist(vsp) => Ok(vsp
             .iter()
             .map(lp)
             .collect::<Result<Vec<_>>>()?
             .iter()
-            .any(|role| role.is_fascist()))
+            .any(|role| role.is_fascist())),
+        Information::SameTeam(l, r) => Ok(lp(l)?.is_fascist() == lp(r)?.is_fascist()),
+        Information::OppositeTeams(l, r) => Ok(lp(l)?.is_fascist() != lp(r)?.is_fascist()),
+        Information::ExactlyKFascists(players, count) => {
+            Ok(count_fascists(roles, players)? == *count)
+        },
+        Information::AtMostKFascists(players, count) => {
+            Ok(count_fascists(roles, players)? <= *count)
+        },
+        Information::HitlerAmong(players) => Ok(players
+            .iter()
+            .map(lp)
+            .collect::<Result<Vec<_>>>()?
+            .iter()
+            .any(|role| **role == SecretRole::Hitler)),
+        Information::Custom(expression) => expression.evaluate(roles)
     }
 }
 
+fn count_fascists(roles : &BTreeMap<PlayerID, SecretRole>, players : &[PlayerID]) -> Result<usize> {
+    players.iter().try_fold(0, |count, pid| {
+        let role = roles.get(pid).ok_or(Error::BadPlayerID(*pid))?;
+        Ok(count + usize::from(role.is_fascist()))
+    })
+}
+
 pub(super) fn valid_role_assignments(
     roles : &BTreeMap<PlayerID, SecretRole>,
     information : &[Information],
@@ -98,17 +122,18 @@ pub(super) fn filter_assigned_roles_inconvenient(
     allow_aggressive_hitler : bool,
     temporary_infomration : &[Information]
 ) -> Result<Vec<BTreeMap<usize, SecretRole>>> {
+    let information = player_state
+        .collect_information()
+        .into_iter()
+        .chain(temporary_infomration.iter().cloned())
+        .collect_vec();
     let filtered_assignments = player_state
         .current_roles()
         .into_iter()
         .filter(|roles| {
             valid_role_assignments(
                 roles,
-                &player_state
-                    .collect_information()
-                    .into_iter()
-                    .chain(temporary_infomration.iter().cloned())
-                    .collect_vec(),
+                &information,
                 !allow_aggressive_hitler,
                 !allow_fascist_fascist_conflict
             )
@@ -143,6 +168,9 @@ pub(super) fn filter_assigned_roles(
     )
 }
 
+/// Counts the roles of each player over all role assignments allowed by the
+/// information. Every assignment is weighted by the enabled soft reads, so
+/// without any the counts are plain numbers of assignments.
 #[debug_invariant(player_state.invariant())]
 pub(super) fn filtered_histogramm(
     (allow_fascist_fascist_conflict, allow_aggressive_hitler) : (bool, bool),
@@ -154,41 +182,42 @@ pub(super) fn filtered_histogramm(
         player_state,
         temporary_infomration
     )?;
+    let reads = player_state
+        .soft_reads
+        .iter()
+        .filter(|read| read.enabled)
+        .collect_vec();
 
-    Ok(filtered_assignments
-        .into_iter()
-        .flat_map(|ra| ra.into_iter())
-        .sorted_by_key(|(pid, _role)| *pid)
-        .group_by(|(pid, _role)| *pid)
+    let mut weighted : BTreeMap<PlayerID, HashMap<SecretRole, usize>> = BTreeMap::new();
+    let mut total : usize = 0;
+    for roles in filtered_assignments {
+        let weight = reads.iter().try_fold(1usize, |weight, read| {
+            let role = roles
+                .get(&read.player)
+                .ok_or(Error::BadPlayerID(read.player))?;
+            weight
+                .checked_mul(read.weight(*role))
+                .ok_or(Error::TooManyReads)
+        })?;
+        total = total.checked_add(weight).ok_or(Error::TooManyReads)?;
+        for (pid, role) in roles {
+            *weighted.entry(pid).or_default().entry(role).or_default() += weight;
+        }
+    }
+
+    Ok(weighted
         .into_iter()
-        .map(|(pid, group)| {
-            let counted = group
-                .into_iter()
-                .map(|(_pid, role)| role)
-                .sorted()
-                .group_by(|r| *r)
-                .into_iter()
-                .map(|(role, group)| {
-                    (
-                        role,
-                        FilterResult {
-                            num_matching : group.count(),
-                            num_checked : 0
-                        }
-                    )
-                })
-                .collect::<HashMap<_, _>>();
-            let total = counted.iter().map(|(_pid, count)| count.num_matching).sum();
+        .map(|(pid, counted)| {
             (
                 pid,
                 (
                     counted
                         .into_iter()
-                        .map(|(pid, count)| {
+                        .map(|(role, num_matching)| {
                             (
-                                pid,
+                                role,
                                 FilterResult {
-                                    num_matching : count.num_matching,
+                                    num_matching,
                                     num_checked : total
                                 }
                             )
@@ -0,0 +1,189 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use itertools::Itertools;
+
+use crate::{
+    deck::constrained_window_histogram,
+    error::{Error, Result},
+    policy::Policy
+};
+
+use super::{filter_engine::filter_assigned_roles, liars::shuffle_window_constraints, PlayerState};
+
+/// The deck the next government draws from.
+pub(super) struct NextDraw {
+    /// the liberal and fascist policies in the deck when it got shuffled
+    pub shuffled_liberal : usize,
+    pub shuffled_fascist : usize,
+    /// whether nothing was drawn from the deck since it got shuffled
+    pub fresh : bool,
+    /// the amount of liberal policies among the next cards, counted over all
+    /// decks consistent with what happened since the shuffle
+    pub histogram : BTreeMap<usize, usize>
+}
+
+/// Computes the distribution of liberal policies among the next
+/// `window_size` cards of the tracked deck. If `truthful` is set, every claim
+/// within the current shuffle is assumed to be true, otherwise only the passed
+/// policies and top decks are taken into account.
+pub(super) fn next_draw(
+    player_state : &PlayerState,
+    window_size : usize,
+    truthful : bool
+) -> Result<NextDraw> {
+    let configuration = &player_state.table_configuration;
+    let next_context = player_state.build_next_card_context()?;
+    let shuffles = player_state.shuffle_election_results();
+    let current_shuffle = shuffles
+        .last()
+        .filter(|shuffle| shuffle.shuffle_index == next_context.shuffle_index)
+        // fewer than three cards force a reshuffle before the next draw
+        .filter(|_shuffle| next_context.cards_left >= 3);
+
+    let (shuffled_liberal, shuffled_fascist, offset, constraints) = match current_shuffle {
+        Some(shuffle) => (
+            shuffle.initial_deck_liberal,
+            shuffle.initial_deck_fascist,
+            shuffle
+                .election_results
+                .iter()
+                .map(|er| er.cards_total_drawn_discarded().0)
+                .sum(),
+            shuffle_window_constraints(shuffle, true)
+                .into_iter()
+                .filter(|constraint| truthful || constraint.player.is_none())
+                .collect()
+        ),
+        None => (
+            configuration.initial_liberal_deck_policies
+                + configuration.initial_placed_liberal_policies
+                - player_state.count_policies_on_board(Policy::Liberal),
+            configuration.initial_fascist_deck_policies
+                + configuration.initial_placed_fascist_policies
+                - player_state.count_policies_on_board(Policy::Fascist),
+            0,
+            vec![]
+        )
+    };
+
+    let cards_left = (shuffled_liberal + shuffled_fascist).saturating_sub(offset);
+    if window_size > cards_left {
+        return Err(Error::TooLongPatternError {
+            have : cards_left,
+            requested : window_size
+        });
+    }
+
+    let histogram = constrained_window_histogram(
+        shuffled_liberal,
+        shuffled_fascist,
+        constraints,
+        offset,
+        window_size
+    );
+    if histogram.is_empty() {
+        return Err(Error::ImpossibleDeck);
+    }
+
+    Ok(NextDraw {
+        shuffled_liberal,
+        shuffled_fascist,
+        fresh : current_shuffle.is_none(),
+        histogram
+    })
+}
+
+/// The current draw and discard pile.
+pub(super) struct Piles {
+    pub draw_pile_cards : usize,
+    pub discard_pile_cards : usize,
+    /// the liberal policies not on the board
+    pub liberals_off_board : usize,
+    /// the weight of each amount of liberal policies in the draw pile
+    pub draw_pile_liberals : BTreeMap<usize, usize>
+}
+
+/// Computes how many liberal policies are in the draw pile (and hence in the
+/// discard pile). Every pair of a role assignment allowed by the filter
+/// engine and a deck consistent with the claims of the liberals in that
+/// assignment is considered equally likely.
+pub(super) fn current_piles(
+    filter_args : (bool, bool),
+    player_state : &PlayerState
+) -> Result<Piles> {
+    let configuration = &player_state.table_configuration;
+    let liberals_off_board = configuration.initial_liberal_deck_policies
+        + configuration.initial_placed_liberal_policies
+        - player_state.count_policies_on_board(Policy::Liberal);
+    let cards_off_board = liberals_off_board
+        + configuration.initial_fascist_deck_policies
+        + configuration.initial_placed_fascist_policies
+        - player_state.count_policies_on_board(Policy::Fascist);
+    let assignments = filter_assigned_roles(filter_args, player_state, &[])?;
+
+    let next_context = player_state.build_next_card_context()?;
+    let shuffles = player_state.shuffle_election_results();
+    let current_shuffle = match shuffles.last() {
+        Some(shuffle) if shuffle.shuffle_index == next_context.shuffle_index => shuffle,
+        // everything not on the board just got shuffled into the draw pile
+        _ => {
+            return Ok(Piles {
+                draw_pile_cards : cards_off_board,
+                discard_pile_cards : 0,
+                liberals_off_board,
+                draw_pile_liberals : [(liberals_off_board, assignments.len())].into()
+            })
+        },
+    };
+
+    let deck_size = current_shuffle.initial_deck_liberal + current_shuffle.initial_deck_fascist;
+    let offset : usize = current_shuffle
+        .election_results
+        .iter()
+        .map(|er| er.cards_total_drawn_discarded().0)
+        .sum();
+    let constraints = shuffle_window_constraints(current_shuffle, true);
+    let claimants = constraints
+        .iter()
+        .filter_map(|constraint| constraint.player)
+        .collect::<BTreeSet<_>>();
+
+    let mut draw_pile_liberals = BTreeMap::new();
+    for (truthful, count) in assignments
+        .iter()
+        .map(|roles| {
+            claimants
+                .iter()
+                .copied()
+                .filter(|pid| !roles[pid].is_fascist())
+                .collect::<BTreeSet<_>>()
+        })
+        .counts()
+    {
+        let histogram = constrained_window_histogram(
+            current_shuffle.initial_deck_liberal,
+            current_shuffle.initial_deck_fascist,
+            constraints
+                .iter()
+                .filter(|constraint| constraint.player.is_none_or(|pid| truthful.contains(&pid)))
+                .cloned()
+                .collect(),
+            offset,
+            deck_size.saturating_sub(offset)
+        );
+        for (liberals, decks) in histogram {
+            *draw_pile_liberals.entry(liberals).or_insert(0) += count * decks;
+        }
+    }
+
+    if draw_pile_liberals.is_empty() {
+        return Err(Error::ImpossibleDeck);
+    }
+
+    Ok(Piles {
+        draw_pile_cards : deck_size.saturating_sub(offset),
+        discard_pile_cards : current_shuffle.total_discarded,
+        liberals_off_board,
+        draw_pile_liberals
+    })
+}
@@ -18,7 +18,7 @@ use crate::{
 
 use super::{PlayerInfo, PresidentialAction, PresidentialAction::*};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[readonly::make]
 pub(crate) struct GameConfiguration {
     pub table_size : usize,
@@ -59,6 +59,9 @@ const LARGE_BOARD : [PresidentialAction; 5] = [
 ];
 
 impl GameConfiguration {
+    /// Hitler only gets to know the fascists in games of up to six players.
+    pub(crate) fn hitler_knows_fascists(&self) -> bool { self.table_size <= 6 }
+
     pub(crate) fn new_standard(table_size : usize, rebalanced : bool) -> Result<Self> {
         Ok(GameConfiguration {
             table_size,
@@ -0,0 +1,35 @@
+use super::PlayerState;
+
+/// Snapshots of the player state taken right before each mutating command,
+/// used to provide undo and redo.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct History {
+    undo_stack : Vec<(String, PlayerState)>,
+    redo_stack : Vec<(String, PlayerState)>
+}
+
+impl History {
+    /// Remembers the state right before it gets changed by `action`.
+    /// Invalidates everything that could have been redone.
+    pub(crate) fn record(&mut self, player_state : &PlayerState, action : impl Into<String>) {
+        self.undo_stack.push((action.into(), player_state.clone()));
+        self.redo_stack.clear();
+    }
+
+    /// Restores the state before the latest recorded change and returns what
+    /// that change was.
+    pub(super) fn undo(&mut self, player_state : &mut PlayerState) -> Option<String> {
+        let (action, snapshot) = self.undo_stack.pop()?;
+        let undone = player_state.restore(snapshot);
+        self.redo_stack.push((action.clone(), undone));
+        Some(action)
+    }
+
+    /// Re-applies the latest undone change and returns what that change was.
+    pub(super) fn redo(&mut self, player_state : &mut PlayerState) -> Option<String> {
+        let (action, snapshot) = self.redo_stack.pop()?;
+        let redone = player_state.restore(snapshot);
+        self.undo_stack.push((action.clone(), redone));
+        Some(action)
+    }
+}
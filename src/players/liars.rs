@@ -0,0 +1,177 @@
+use std::collections::BTreeSet;
+
+use itertools::Itertools;
+
+use crate::{
+    deck::{minimal_liar_sets, WindowConstraint},
+    information::{Information, Origin},
+    policy::Policy,
+    PlayerID
+};
+
+use super::{ElectionResult::*, PlayerState, PresidentialAction::*, ShuffleAnalysis};
+
+/// Translates everything that happened within a shuffle into constraints on
+/// the windows of the shuffled deck. Drawn cards and top decks are known,
+/// presidential and chancellor claims as well as peeks are claims of the
+/// respective player. Peeks are only considered if the peeked cards are drawn
+/// from the same shuffle.
+pub(super) fn shuffle_window_constraints(
+    shuffle : &ShuffleAnalysis,
+    continues_after_shuffle : bool
+) -> Vec<WindowConstraint> {
+    let deck_size = shuffle.initial_deck_liberal + shuffle.initial_deck_fascist;
+    let mut constraints = vec![];
+    let mut offset = 0;
+
+    for (index, er) in shuffle.election_results.iter().enumerate() {
+        let (drawn, _discarded) = er.cards_total_drawn_discarded();
+        let followed_up = index + 1 < shuffle.election_results.len() || continues_after_shuffle;
+
+        match er {
+            TopDeck(policy, _) => {
+                let blues = usize::from(*policy == Policy::Liberal);
+                constraints.push(WindowConstraint {
+                    player : None,
+                    offset,
+                    length : 1,
+                    min_blues : blues,
+                    max_blues : blues
+                });
+            },
+            Election(gov) => {
+                constraints.push(match gov.policy_passed {
+                    Policy::Liberal => WindowConstraint {
+                        player : None,
+                        offset,
+                        length : 3,
+                        min_blues : 1,
+                        max_blues : 3
+                    },
+                    Policy::Fascist => WindowConstraint {
+                        player : None,
+                        offset,
+                        length : 3,
+                        min_blues : 0,
+                        max_blues : 2
+                    }
+                });
+                // unknown claims don't constrain anything
+                if let Some(blues) = gov.president_claimed_blues {
+                    constraints.push(WindowConstraint {
+                        player : Some(gov.president),
+                        offset,
+                        length : 3,
+                        min_blues : blues,
+                        max_blues : blues
+                    });
+                }
+                // the chancellor claims to have received these cards out of the three drawn
+                if let Some(blues) = gov.chancellor_claimed_blues {
+                    constraints.push(WindowConstraint {
+                        player : Some(gov.chancellor),
+                        offset,
+                        length : 3,
+                        min_blues : blues,
+                        max_blues : blues + 1
+                    });
+                }
+
+                let peeked = match gov.presidential_action {
+                    TopDeckPeek(peek) if followed_up => Some((
+                        offset + 3,
+                        3,
+                        peek.iter().filter(|p| **p == Policy::Liberal).count()
+                    )),
+                    PeekAndBurn(policy, false, _) if followed_up => {
+                        Some((offset + 3, 1, usize::from(policy == Policy::Liberal)))
+                    },
+                    PeekAndBurn(policy, true, _) => {
+                        Some((offset + 3, 1, usize::from(policy == Policy::Liberal)))
+                    },
+                    _ => None
+                };
+                if let Some((peek_offset, length, blues)) = peeked {
+                    if peek_offset + length <= deck_size {
+                        constraints.push(WindowConstraint {
+                            player : Some(gov.president),
+                            offset : peek_offset,
+                            length,
+                            min_blues : blues,
+                            max_blues : blues
+                        });
+                    }
+                }
+            }
+        }
+
+        offset += drawn;
+    }
+
+    constraints
+}
+
+/// Computes the minimal sets of players who must have lied for the shuffle to
+/// be consistent. `None` if not even the drawn cards are consistent.
+pub(super) fn shuffle_liar_sets(
+    player_state : &PlayerState,
+    shuffle : &ShuffleAnalysis
+) -> Option<Vec<BTreeSet<PlayerID>>> {
+    let continues_after_shuffle = player_state
+        .build_next_card_context()
+        .is_ok_and(|next_context| next_context.shuffle_index == shuffle.shuffle_index);
+    let liar_sets = minimal_liar_sets(
+        shuffle.initial_deck_liberal,
+        shuffle.initial_deck_fascist,
+        shuffle_window_constraints(shuffle, continues_after_shuffle)
+    );
+    (!liar_sets.is_empty()).then_some(liar_sets)
+}
+
+/// The minimal sets of players that contain at least one member of each liar
+/// set, i.e. the minimal groups that can't all have told the truth.
+pub(super) fn minimal_transversals(liar_sets : &[BTreeSet<PlayerID>]) -> Vec<BTreeSet<PlayerID>> {
+    if liar_sets.iter().any(|liars| liars.is_empty()) {
+        return vec![];
+    }
+
+    let candidates = liar_sets.iter().flatten().copied().unique().collect_vec();
+    let mut transversals : Vec<BTreeSet<PlayerID>> = vec![];
+
+    for size in 1..=candidates.len() {
+        let mut found = candidates
+            .iter()
+            .copied()
+            .combinations(size)
+            .map(|players| players.into_iter().collect::<BTreeSet<_>>())
+            .filter(|players| !transversals.iter().any(|known| known.is_subset(players)))
+            .filter(|players| liar_sets.iter().all(|liars| !liars.is_disjoint(players)))
+            .collect_vec();
+        transversals.append(&mut found);
+    }
+
+    transversals
+}
+
+/// For each shuffle, every group of players that can't all have told the
+/// truth contains at least one fascist.
+pub(super) fn card_count_deductions(player_state : &PlayerState) -> Vec<(Information, Origin)> {
+    player_state
+        .shuffle_election_results()
+        .iter()
+        .filter_map(|shuffle| {
+            shuffle_liar_sets(player_state, shuffle)
+                .map(|liar_sets| (shuffle.shuffle_index, liar_sets))
+        })
+        .flat_map(|(shuffle_index, liar_sets)| {
+            minimal_transversals(&liar_sets)
+                .into_iter()
+                .map(move |suspicious_players| {
+                    (
+                        Information::AtLeastOneFascist(suspicious_players.into_iter().collect()),
+                        Origin::Shuffle(shuffle_index)
+                    )
+                })
+        })
+        .collect()
+}
@@ -0,0 +1,667 @@
+use std::str::FromStr;
+
+use itertools::Itertools;
+
+use crate::{
+    deck::parse_pattern,
+    error::{Error, Result},
+    information::Information,
+    policy::Policy,
+    secret_role::SecretRole,
+    PlayerID
+};
+
+use super::{
+    parse_claim, parse_player_name, ElectionResult::*, PlayerInfos, PlayerManager, PlayerState,
+    PresidentialAction, PresidentialAction::*
+};
+
+/// A whitespace-separated piece of a log line together with its (1-based)
+/// column for error reporting.
+#[derive(Clone, Copy)]
+struct Token<'a> {
+    text : &'a str,
+    column : usize
+}
+
+impl<'a> Token<'a> {
+    fn split_once(&self, separator : char) -> Option<(Token<'a>, Token<'a>)> {
+        let (left, right) = self.text.split_once(separator)?;
+        Some((
+            Token {
+                text : left,
+                column : self.column
+            },
+            Token {
+                text : right,
+                column : self.column + left.chars().count() + 1
+            }
+        ))
+    }
+}
+
+/// An error together with the column it was caused at, 0 if the line ended
+/// prematurely.
+type LineResult<T> = std::result::Result<T, (usize, Error)>;
+
+/// An error together with the (line, column) it was caused at.
+pub(super) type PositionedError = (usize, usize, Error);
+
+enum LogEntry {
+    Government {
+        president : PlayerID,
+        chancellor : PlayerID,
+        president_claimed_blues : Option<usize>,
+        chancellor_claimed_blues : Option<usize>,
+        enacted : Option<Policy>,
+        action : Option<(PresidentialAction, usize)>
+    },
+    TopDeck(Policy),
+    Action(PresidentialAction, Option<PlayerID>),
+    Fact(Information)
+}
+
+#[derive(Default)]
+pub(super) struct ImportSummary {
+    pub governments : usize,
+    pub top_decks : usize,
+    pub facts : usize
+}
+
+fn tokenize(line : &str) -> Vec<Token<'_>> {
+    let mut tokens = vec![];
+    let mut start = None;
+    for (column, (byte_index, c)) in line.char_indices().enumerate() {
+        match (c.is_whitespace(), start) {
+            (false, None) => start = Some((byte_index, column + 1)),
+            (true, Some((begin, token_column))) => {
+                tokens.push(Token {
+                    text : &line[begin..byte_index],
+                    column : token_column
+                });
+                start = None;
+            },
+            _ => {}
+        }
+    }
+    if let Some((begin, column)) = start {
+        tokens.push(Token {
+            text : &line[begin..],
+            column
+        });
+    }
+    tokens
+}
+
+fn at(token : &Token, error : Error) -> (usize, Error) { (token.column, error) }
+
+fn expect<'a, 'b>(
+    tokens : &mut impl Iterator<Item = &'b Token<'a>>,
+    what : &'static str
+) -> LineResult<&'b Token<'a>>
+where
+    'a : 'b
+{
+    tokens.next().ok_or((0, Error::MissingLogToken(what)))
+}
+
+fn player(token : &Token, player_info : &PlayerInfos) -> LineResult<PlayerID> {
+    let pid = parse_player_name(token.text, player_info).map_err(|e| at(token, e))?;
+    player_info.player_exists(pid).map_err(|e| at(token, e))?;
+    Ok(pid)
+}
+
+fn party(token : &Token) -> LineResult<Policy> {
+    Policy::from_str(token.text)
+        .or_else(|_| match token.text.to_lowercase().as_str() {
+            "red" | "fasc" => Ok(Policy::Fascist),
+            text => SecretRole::from_str(text).map(|role| {
+                if role.is_fascist() {
+                    Policy::Fascist
+                }
+                else {
+                    Policy::Liberal
+                }
+            })
+        })
+        .map_err(|e| at(token, e))
+}
+
+fn claim(token : &Token, length : usize) -> LineResult<Option<usize>> {
+    parse_claim(token.text, length).map_err(|e| at(token, e))
+}
+
+/// A chancellor claim optionally followed by the enacted policy, e.g. `rb:r`.
+fn chancellor_claim(token : &Token) -> LineResult<(Option<usize>, Option<Policy>)> {
+    match token.split_once(':') {
+        Some((claimed, enacted)) => Ok((claim(&claimed, 2)?, Some(party(&enacted)?))),
+        None => Ok((claim(token, 2)?, None))
+    }
+}
+
+fn policies(token : &Token) -> LineResult<Vec<Policy>> {
+    Ok(parse_pattern(token.text.to_string(), 3, 1)
+        .map_err(|e| at(token, e))?
+        .2)
+}
+
+fn finish<'a, 'b>(mut tokens : impl Iterator<Item = &'b Token<'a>>) -> LineResult<()>
+where
+    'a : 'b
+{
+    match tokens.next() {
+        None => Ok(()),
+        Some(token) => Err(at(token, Error::UnexpectedLogToken(token.text.to_string())))
+    }
+}
+
+/// Parses presidential actions that either directly follow a government or are
+/// placed on their own line, e.g. `kill 5`, `inv 2 lib`, `peek rrb` or `burn
+/// r`. The investigator for `<player> inv <player> <party>` is returned
+/// separately.
+fn parse_action<'a, 'b>(
+    keyword : &'b Token<'a>,
+    tokens : &mut impl Iterator<Item = &'b Token<'a>>,
+    player_info : &PlayerInfos
+) -> LineResult<Option<PresidentialAction>>
+where
+    'a : 'b
+{
+    Ok(Some(match keyword.text.to_lowercase().as_str() {
+        "kill" | "shoot" | "shot" => Kill(player(expect(tokens, "player")?, player_info)?),
+        "se" | "pick" | "special" => {
+            SpecialElection(player(expect(tokens, "player")?, player_info)?)
+        },
+        "inv" | "investigate" | "investigates" => Investigation(
+            player(expect(tokens, "player")?, player_info)?,
+            party(expect(tokens, "party")?)?
+        ),
+        "reveal" | "shows" => RevealParty(
+            player(expect(tokens, "player")?, player_info)?,
+            party(expect(tokens, "party")?)?
+        ),
+        "peek" => {
+            let peek_token = expect(tokens, "peek result")?;
+            let peeked = policies(peek_token)?;
+            match peeked.len() {
+                3 => TopDeckPeek(peeked.try_into().unwrap()),
+                1 => PeekAndBurn(peeked[0], false, Default::default()),
+                requested => {
+                    return Err(at(
+                        peek_token,
+                        Error::TooShortPatternError {
+                            have : 3,
+                            requested
+                        }
+                    ))
+                },
+            }
+        },
+        "burn" => PeekAndBurn(party(expect(tokens, "policy")?)?, true, Default::default()),
+        _ => return Ok(None)
+    }))
+}
+
+fn parse_line(tokens : &[Token], player_info : &PlayerInfos) -> LineResult<LogEntry> {
+    let mut iter = tokens.iter();
+    let first = expect(&mut iter, "command")?;
+
+    if let Some((president, chancellor)) = first.split_once('>') {
+        let president = player(&president, player_info)?;
+        let chancellor = player(&chancellor, player_info)?;
+        let claims = expect(&mut iter, "claim")?;
+        let (president_claimed_blues, (chancellor_claimed_blues, enacted)) =
+            match claims.split_once('/') {
+                Some((president_claim, chancellor)) => {
+                    (claim(&president_claim, 3)?, chancellor_claim(&chancellor)?)
+                },
+                None => (
+                    claim(claims, 3)?,
+                    chancellor_claim(expect(&mut iter, "claim")?)?
+                )
+            };
+        let action = match iter.next() {
+            None => None,
+            Some(keyword) => match parse_action(keyword, &mut iter, player_info)? {
+                Some(action) => Some((action, keyword.column)),
+                None => {
+                    return Err(at(
+                        keyword,
+                        Error::UnexpectedLogToken(keyword.text.to_string())
+                    ))
+                },
+            }
+        };
+        finish(iter)?;
+        return Ok(LogEntry::Government {
+            president,
+            chancellor,
+            president_claimed_blues,
+            chancellor_claimed_blues,
+            enacted,
+            action
+        });
+    }
+
+    match first.text.to_lowercase().as_str() {
+        "topdeck" | "td" => {
+            let policy = party(expect(&mut iter, "policy")?)?;
+            finish(iter)?;
+            return Ok(LogEntry::TopDeck(policy));
+        },
+        "conflict" => {
+            let left = player(expect(&mut iter, "player")?, player_info)?;
+            let right = player(expect(&mut iter, "player")?, player_info)?;
+            finish(iter)?;
+            return Ok(LogEntry::Fact(Information::PolicyConflict(left, right)));
+        },
+        _ => {}
+    }
+
+    if let Some(action) = parse_action(first, &mut iter, player_info)? {
+        finish(iter)?;
+        return Ok(LogEntry::Action(action, None));
+    }
+
+    let subject = player(first, player_info)?;
+    let keyword = expect(&mut iter, "keyword")?;
+    let entry = match keyword.text.to_lowercase().as_str() {
+        "is" => {
+            let role = expect(&mut iter, "role")?;
+            LogEntry::Fact(Information::HardFact(
+                subject,
+                SecretRole::from_str(role.text).map_err(|e| at(role, e))?
+            ))
+        },
+        "nh" => LogEntry::Fact(Information::ConfirmedNotHitler(subject)),
+        "not" => {
+            let hitler = expect(&mut iter, "\"hitler\"")?;
+            if SecretRole::from_str(hitler.text).ok() != Some(SecretRole::Hitler) {
+                return Err(at(
+                    hitler,
+                    Error::UnexpectedLogToken(hitler.text.to_string())
+                ));
+            }
+            LogEntry::Fact(Information::ConfirmedNotHitler(subject))
+        },
+        "conflict" => LogEntry::Fact(Information::PolicyConflict(
+            subject,
+            player(expect(&mut iter, "player")?, player_info)?
+        )),
+        "and" => {
+            let other = player(expect(&mut iter, "player")?, player_info)?;
+            let relation = expect(&mut iter, "\"conflict\", \"same\" or \"opposite\"")?;
+            LogEntry::Fact(match relation.text.to_lowercase().as_str() {
+                "conflict" => Information::PolicyConflict(subject, other),
+                "same" => Information::SameTeam(subject, other),
+                "opposite" => Information::OppositeTeams(subject, other),
+                _ => {
+                    return Err(at(
+                        relation,
+                        Error::UnexpectedLogToken(relation.text.to_string())
+                    ))
+                },
+            })
+        },
+        _ => match parse_action(keyword, &mut iter, player_info)? {
+            Some(action @ (Investigation(_, _) | RevealParty(_, _))) => {
+                LogEntry::Action(action, Some(subject))
+            },
+            _ => {
+                return Err(at(
+                    keyword,
+                    Error::UnexpectedLogToken(keyword.text.to_string())
+                ))
+            },
+        }
+    };
+    finish(iter)?;
+    Ok(entry)
+}
+
+fn investigation_fact(
+    investigator : PlayerID,
+    investigatee : PlayerID,
+    result : Policy
+) -> Information {
+    match result {
+        Policy::Liberal => Information::LiberalInvestigation {
+            investigator,
+            investigatee
+        },
+        Policy::Fascist => Information::FascistInvestigation {
+            investigator,
+            investigatee
+        }
+    }
+}
+
+/// Turns stand-alone investigations that don't belong to the preceding
+/// government's president into plain facts and attaches all other stand-alone
+/// actions to the government right before them.
+fn assemble(
+    entries : Vec<(usize, usize, LogEntry)>
+) -> std::result::Result<Vec<(usize, usize, LogEntry)>, Vec<PositionedError>> {
+    let mut assembled : Vec<(usize, usize, LogEntry)> = vec![];
+    let mut errors = vec![];
+
+    for (line, column, entry) in entries {
+        let pending_president = match assembled.last() {
+            Some((
+                _,
+                _,
+                LogEntry::Government {
+                    president,
+                    action: None,
+                    ..
+                }
+            )) => Some(*president),
+            _ => None
+        };
+        match entry {
+            LogEntry::Action(Investigation(investigatee, result), Some(investigator))
+                if pending_president != Some(investigator) =>
+            {
+                assembled.push((
+                    line,
+                    column,
+                    LogEntry::Fact(investigation_fact(investigator, investigatee, result))
+                ))
+            },
+            LogEntry::Action(presidential_action, _) => match assembled.last_mut() {
+                Some((_, _, LogEntry::Government { action, .. }))
+                    if pending_president.is_some() =>
+                {
+                    *action = Some((presidential_action, column))
+                },
+                _ => errors.push((line, column, Error::DanglingPresidentialAction))
+            },
+            entry => assembled.push((line, column, entry))
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(assembled)
+    }
+    else {
+        Err(errors)
+    }
+}
+
+/// Parses a single fact written like in a game log, e.g. `4 is lib`, `2 and 5
+/// conflict`, `2 and 6 same` or `1 inv 3 fasc`.
+pub(super) fn parse_fact(text : &str, player_info : &PlayerInfos) -> Result<Information> {
+    match parse_line(&tokenize(text), player_info).map_err(|(_column, error)| error)? {
+        LogEntry::Fact(information) => Ok(information),
+        LogEntry::Action(Investigation(investigatee, result), Some(investigator)) => {
+            Ok(investigation_fact(investigator, investigatee, result))
+        },
+        _ => Err(Error::NotAFact(text.to_owned()))
+    }
+}
+
+/// Parses a free-form game log (one event per line) as typically typed into
+/// the chat and applies it to a copy of the given state. Either all lines get
+/// applied or all errors with their positions are reported.
+pub(super) fn import_log(
+    text : &str,
+    player_state : &PlayerState
+) -> std::result::Result<(PlayerState, ImportSummary), Vec<PositionedError>> {
+    let (entries, errors) : (Vec<_>, Vec<_>) = text
+        .lines()
+        .enumerate()
+        .map(|(line, text)| (line + 1, text.trim_end()))
+        .filter(|(_line, text)| {
+            let trimmed = text.trim_start();
+            !trimmed.is_empty() && !trimmed.starts_with('#') && !trimmed.starts_with("//")
+        })
+        .map(|(line, text)| {
+            let tokens = tokenize(text);
+            let column = tokens.first().map(|t| t.column).unwrap_or(1);
+            parse_line(&tokens, &player_state.player_info)
+                .map(|entry| (line, column, entry))
+                .map_err(|(error_column, error)| {
+                    (
+                        line,
+                        if error_column == 0 {
+                            text.chars().count() + 1
+                        }
+                        else {
+                            error_column
+                        },
+                        error
+                    )
+                })
+        })
+        .partition_result();
+
+    let entries = match (assemble(entries), errors) {
+        (Ok(entries), errors) if errors.is_empty() => entries,
+        (Ok(_), errors) => return Err(errors),
+        (Err(assembly_errors), errors) => {
+            return Err(errors
+                .into_iter()
+                .chain(assembly_errors)
+                .sorted_by_key(|(line, column, _)| (*line, *column))
+                .collect())
+        },
+    };
+
+    let mut staged = player_state.clone();
+    let mut summary = ImportSummary::default();
+
+    for (line, column, entry) in entries {
+        match entry {
+            LogEntry::Government {
+                president,
+                chancellor,
+                president_claimed_blues,
+                chancellor_claimed_blues,
+                enacted,
+                action
+            } => {
+                let action_column = action.map(|(_, column)| column).unwrap_or(column);
+                let government = staged
+                    .build_government(
+                        president,
+                        chancellor,
+                        president_claimed_blues,
+                        chancellor_claimed_blues,
+                        enacted,
+                        |granted, deck_context| {
+                            staged.validate_presidential_action(
+                                granted,
+                                action.map(|(action, _)| action).unwrap_or(NoAction),
+                                deck_context
+                            )
+                        }
+                    )
+                    .map_err(|e| {
+                        let column = match e {
+                            Error::PresidentialActionMismatch { .. } => action_column,
+                            _ => column
+                        };
+                        vec![(line, column, e)]
+                    })?
+                    .ok_or_else(|| vec![(line, column, Error::GameAlreadyDecided)])?;
+                let _ = staged.governments.push(Election(government));
+                summary.governments += 1;
+            },
+            LogEntry::TopDeck(policy) => {
+                let deck_context = staged
+                    .build_next_card_context()
+                    .map_err(|error| vec![(line, column, error)])?;
+                let _ = staged.governments.push(TopDeck(policy, deck_context));
+                summary.top_decks += 1;
+            },
+            LogEntry::Fact(information) => {
+                let _ = staged.available_information.push(information);
+                summary.facts += 1;
+            },
+            LogEntry::Action(_, _) => unreachable!()
+        }
+    }
+
+    Ok((staged, summary))
+}
+
+pub(super) fn format_import_errors(errors : &[PositionedError]) -> String {
+    errors
+        .iter()
+        .map(|(line, column, error)| format!("line {line}, column {column}: {error}"))
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{super::game_configuration::GameConfiguration, *};
+
+    fn seven_players() -> PlayerState {
+        PlayerState::new(GameConfiguration::new_standard(7, false).unwrap())
+    }
+
+    fn line(text : &str) -> LineResult<LogEntry> {
+        parse_line(&tokenize(text), &seven_players().player_info)
+    }
+
+    #[test]
+    fn government_shorthands() {
+        let Ok(LogEntry::Government {
+            president,
+            chancellor,
+            president_claimed_blues,
+            chancellor_claimed_blues,
+            enacted,
+            action
+        }) = line("1>2 rrb/rb kill 5")
+        else {
+            panic!("expected a government");
+        };
+        assert_eq!((president, chancellor), (1, 2));
+        assert_eq!(
+            (president_claimed_blues, chancellor_claimed_blues),
+            (Some(1), Some(1))
+        );
+        assert_eq!(enacted, None);
+        assert_eq!(action, Some((Kill(5), 12)));
+
+        let Ok(LogEntry::Government {
+            president_claimed_blues,
+            chancellor_claimed_blues,
+            enacted,
+            action,
+            ..
+        }) = line("3>4 ? bb:r")
+        else {
+            panic!("expected a government");
+        };
+        assert_eq!(
+            (president_claimed_blues, chancellor_claimed_blues),
+            (None, Some(2))
+        );
+        assert_eq!(enacted, Some(Policy::Fascist));
+        assert_eq!(action, None);
+    }
+
+    #[test]
+    fn stand_alone_entries() {
+        assert!(matches!(
+            line("td r"),
+            Ok(LogEntry::TopDeck(Policy::Fascist))
+        ));
+        assert!(matches!(
+            line("peek rrb"),
+            Ok(LogEntry::Action(TopDeckPeek(_), None))
+        ));
+        assert!(matches!(
+            line("burn b"),
+            Ok(LogEntry::Action(
+                PeekAndBurn(Policy::Liberal, true, _),
+                None
+            ))
+        ));
+        assert!(matches!(
+            line("2 inv 3 lib"),
+            Ok(LogEntry::Action(Investigation(3, Policy::Liberal), Some(2)))
+        ));
+    }
+
+    #[test]
+    fn facts() {
+        let player_info = seven_players().player_info;
+        let fact = |text| parse_fact(text, &player_info).unwrap();
+        assert_eq!(
+            fact("4 is lib"),
+            Information::HardFact(4, SecretRole::Liberal)
+        );
+        assert_eq!(fact("3 nh"), Information::ConfirmedNotHitler(3));
+        assert_eq!(fact("3 not hitler"), Information::ConfirmedNotHitler(3));
+        assert_eq!(fact("2 and 5 conflict"), Information::PolicyConflict(2, 5));
+        assert_eq!(fact("2 conflict 5"), Information::PolicyConflict(2, 5));
+        assert_eq!(fact("2 and 6 same"), Information::SameTeam(2, 6));
+        assert_eq!(fact("2 and 6 opposite"), Information::OppositeTeams(2, 6));
+        assert_eq!(
+            fact("1 inv 3 fasc"),
+            Information::FascistInvestigation {
+                investigator : 1,
+                investigatee : 3
+            }
+        );
+        assert!(matches!(
+            parse_fact("1>2 rrr rr", &player_info),
+            Err(Error::NotAFact(_))
+        ));
+    }
+
+    #[test]
+    fn error_columns() {
+        assert!(matches!(
+            line("1>2 rrx rr"),
+            Err((5, Error::ParsePolicyError(_)))
+        ));
+        assert!(matches!(
+            line("1>9 rrr rr"),
+            Err((3, Error::BadPlayerID(9)))
+        ));
+        assert!(matches!(
+            line("1>2 rrr/rr:q"),
+            Err((12, Error::ParseRoleError(_)))
+        ));
+        assert!(matches!(
+            line("1>2 rrr rr shoot"),
+            Err((0, Error::MissingLogToken(_)))
+        ));
+        assert!(matches!(
+            line("1>2 rrr rr dance"),
+            Err((12, Error::UnexpectedLogToken(_)))
+        ));
+    }
+
+    #[test]
+    fn import_assembles_and_positions_errors() {
+        let (staged, summary) = import_log(
+            "# first shuffle\n1>2 rrr rr\n2 inv 3 lib\n\ntd r",
+            &seven_players()
+        )
+        .ok()
+        .unwrap();
+        assert_eq!(
+            (summary.governments, summary.top_decks, summary.facts),
+            (1, 1, 1)
+        );
+        assert_eq!(staged.governments.len(), 2);
+
+        let errors = import_log("kill 3\n1>2 rrr rr\n  1>2 rrr", &seven_players())
+            .err()
+            .unwrap();
+        assert_eq!(
+            errors
+                .iter()
+                .map(|(line, column, _)| (*line, *column))
+                .collect_vec(),
+            vec![(1, 1), (3, 10)]
+        );
+        assert!(matches!(errors[0].2, Error::DanglingPresidentialAction));
+        assert!(matches!(errors[1].2, Error::MissingLogToken(_)));
+    }
+}
@@ -17,7 +17,8 @@ use serde::{Deserialize, Serialize};
 use crate::{
     deck::{next_blues_count, parse_pattern, FilterResult},
     error::{Error, Result},
-    information::Information,
+    expression::{parse_expression, Claim},
+    information::{Derivation, Information, Origin, SoftRead},
     policy::Policy,
     secret_role::SecretRole,
     Context, PlayerID
@@ -31,6 +32,19 @@ pub mod game_configuration;
 use game_configuration::*;
 mod tree;
 use tree::*;
+mod advice;
+mod branches;
+mod explain;
+mod forecast;
+mod history;
+mod liars;
+mod log_import;
+mod perspective;
+mod planning;
+mod simulation;
+pub(crate) use branches::Branches;
+pub(crate) use history::History;
+use perspective::Perspective;
 
 /// CardContext always describes the situation before
 /// the associated (set of) card(s) was drawn
@@ -45,7 +59,8 @@ impl CardContext {
     fn atomic_draw(&self, draw_count : usize, discard_count : usize) -> Self {
         let mut out = *self;
         if self.cards_left.saturating_sub(draw_count) < 3 {
-            out.cards_left += out.cards_discarded;
+            // the leftover cards get shuffled together with all discarded ones
+            out.cards_left = self.cards_left - draw_count + self.cards_discarded + discard_count;
             out.cards_discarded = 0;
             out.shuffle_index += 1;
         }
@@ -65,17 +80,32 @@ pub(crate) trait PlayerManager<K> {
     fn player_exists(&self, key : K) -> Result<()>;
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) struct PlayerState {
     table_configuration : GameConfiguration,
     available_information : CallBackVec<Information>,
     player_info : PlayerInfos,
-    governments : CallBackVec<ElectionResult>
+    governments : CallBackVec<ElectionResult>,
+    /// observed deck sizes overriding the tracked deck before the government
+    /// with the key as its (0-based) index
+    deck_count_assertions : BTreeMap<usize, usize>,
+    /// manually added or deduced information excluded from all analyses
+    disabled_information : Vec<(Information, Origin)>,
+    soft_reads : Vec<SoftRead>,
+    /// the user's own role when playing, restricting the role assignments
+    perspective : Option<Perspective>
 }
 
 impl PlayerState {
     pub(crate) fn current_roles(&self) -> Vec<BTreeMap<PlayerID, SecretRole>> {
-        self.table_configuration.generate_assignments()
+        let assignments = self.table_configuration.generate_assignments();
+        match &self.perspective {
+            None => assignments,
+            Some(perspective) => assignments
+                .into_iter()
+                .filter(|roles| perspective.allows(roles))
+                .collect()
+        }
     }
 
     pub(crate) fn new(table_configuration : GameConfiguration) -> Self {
@@ -84,14 +114,30 @@ impl PlayerState {
             table_configuration,
             available_information : Default::default(),
             player_info,
-            governments : Default::default()
+            governments : Default::default(),
+            deck_count_assertions : Default::default(),
+            disabled_information : Default::default(),
+            soft_reads : Default::default(),
+            perspective : None
         }
     }
 
+    /// Replaces the tracked game with `snapshot` while keeping the registered
+    /// callbacks and returns the replaced game.
+    fn restore(&mut self, mut snapshot : PlayerState) -> PlayerState {
+        snapshot
+            .available_information
+            .adopt_callbacks(&self.available_information);
+        snapshot.governments.adopt_callbacks(&self.governments);
+        std::mem::replace(self, snapshot)
+    }
+
     pub(crate) fn invariant(&self) -> bool {
         self.table_configuration.invariant()
             && self.player_info.len() == self.table_configuration.table_size
-            && self.current_roles() == self.table_configuration.generate_assignments()
+            && (self.perspective.is_some()
+                || self.current_roles() == self.table_configuration.generate_assignments())
+            && !self.current_roles().is_empty()
             && self.current_roles().iter().all(|ra| {
                 ra.len() == self.table_configuration.table_size
                     && ra
@@ -145,6 +191,13 @@ impl PlayerState {
     }
 
     fn is_eligible_president(&self, player : PlayerID) -> bool {
+        // failed elections may skip up to two presidents
+        self.next_presidents(3).contains(&player)
+    }
+
+    /// The next `count` presidents in order, assuming every one of their
+    /// governments gets elected.
+    pub(crate) fn next_presidents(&self, count : usize) -> Vec<PlayerID> {
         let table_size = self.table_configuration.table_size;
         // we also can't "just" inspect the last government because people may have died
         // use an iota vector and a "cursor" to track the state and deaths, as well as
@@ -208,115 +261,361 @@ impl PlayerState {
             }
         }
 
-        for _ in 0..3 {
-            advance_one(
-                &mut current_president,
-                &mut next_president,
-                &dead_players,
-                &mut follow_on_president
-            );
+        (0..count)
+            .map(|_| {
+                advance_one(
+                    &mut current_president,
+                    &mut next_president,
+                    &dead_players,
+                    &mut follow_on_president
+                );
+                current_president
+            })
+            .collect()
+    }
+
+    /// Validates and assembles the government following the currently tracked
+    /// history. The `presidential_action` closure receives the power granted by
+    /// the board (if a fascist policy gets passed) and the deck context of the
+    /// new government and is responsible for filling in its arguments.
+    /// Without an explicitly `enacted` policy, it's inferred from the claims.
+    /// Returns `None` if the fascists already won by policies.
+    fn build_government(
+        &self,
+        president : PlayerID,
+        chancellor : PlayerID,
+        president_claimed_blues : Option<usize>,
+        chancellor_claimed_blues : Option<usize>,
+        enacted : Option<Policy>,
+        presidential_action : impl FnOnce(PresidentialAction, CardContext) -> Result<PresidentialAction>
+    ) -> Result<Option<ElectedGovernment>> {
+        self.player_interactable(president, &self.player_info)?;
+        self.player_interactable(chancellor, &self.player_info)?;
+
+        if !self.is_eligible_president(president) {
+            return Err(Error::NotEligiblePresident(
+                president,
+                self.player_info.clone()
+            ));
+        }
+
+        if !self.is_eligible_chancellor(chancellor) || chancellor == president {
+            return Err(Error::NotEligibleChancellor(
+                chancellor,
+                self.player_info.clone()
+            ));
+        }
+
+        // an unknown claim can't conflict with anything
+        let immediate_conflict = match (president_claimed_blues, chancellor_claimed_blues) {
+            (Some(president_blues), Some(chancellor_blues)) => {
+                claims_conflict(president_blues, chancellor_blues)
+            },
+            _ => false
+        };
+
+        let policy_passed = match (enacted, president_claimed_blues, chancellor_claimed_blues) {
+            (Some(policy), _, _) => policy,
+            (None, Some(0), _) | (None, _, Some(0)) => Policy::Fascist,
+            (None, _, Some(_)) | (None, Some(3), None) => Policy::Liberal,
+            (None, _, None) => return Err(Error::UnknownEnactedPolicy)
+        };
 
-            if current_president == player {
-                return true;
+        let prev_fas_policies = self.count_policies_on_board(Policy::Fascist);
+
+        let deck_context = self.build_next_card_context()?;
+
+        let presidential_action = if policy_passed == Policy::Fascist {
+            if prev_fas_policies >= 5 {
+                return Ok(None);
             }
+
+            presidential_action(
+                self.table_configuration.fascist_board_configuration[prev_fas_policies],
+                deck_context
+            )?
+        }
+        else {
+            presidential_action(NoAction, deck_context)?
+        };
+        self.validate_power_target(president, presidential_action)?;
+
+        Ok(Some(ElectedGovernment {
+            president,
+            chancellor,
+            president_claimed_blues,
+            chancellor_claimed_blues,
+            conflict : immediate_conflict,
+            policy_passed,
+            enacted_known : enacted.is_some(),
+            presidential_action,
+            deck_context,
+            chancellor_confirmed_not_hitler : prev_fas_policies
+                >= self.table_configuration.hitler_zone_passed_fascist_policies
+        }))
+    }
+
+    /// Enforces the rules on whom a president may target beyond them being
+    /// alive.
+    fn validate_power_target(
+        &self,
+        president : PlayerID,
+        action : PresidentialAction
+    ) -> Result<()> {
+        match action {
+            Kill(target)
+            | Investigation(target, _)
+            | RevealParty(target, _)
+            | SpecialElection(target)
+                if target == president =>
+            {
+                Err(Error::SelfTargetedPower(action))
+            },
+            Investigation(target, _)
+                if iter_elected(&self.governments).any(|gov| {
+                    matches!(gov.presidential_action, Investigation(investigated, _) if investigated == target)
+                }) =>
+            {
+                Err(Error::RepeatedInvestigation(target, self.player_info.clone()))
+            },
+            _ => Ok(())
+        }
+    }
+
+    /// Checks that the `provided` action is the one granted by the board and
+    /// that its target can be interacted with.
+    fn validate_presidential_action(
+        &self,
+        granted : PresidentialAction,
+        provided : PresidentialAction,
+        deck_context : CardContext
+    ) -> Result<PresidentialAction> {
+        if std::mem::discriminant(&granted) != std::mem::discriminant(&provided) {
+            return Err(Error::PresidentialActionMismatch { granted, provided });
+        }
+        match provided {
+            Kill(target)
+            | Investigation(target, _)
+            | RevealParty(target, _)
+            | SpecialElection(target) => self.player_interactable(target, &self.player_info)?,
+            _ => {}
+        }
+        Ok(match provided {
+            PeekAndBurn(policy, discarded, _) => {
+                PeekAndBurn(policy, discarded, deck_context.atomic_draw(3, 2))
+            },
+            provided => provided
+        })
+    }
+
+    /// Re-derives the deck contexts and board-dependent properties of `later`
+    /// after a change earlier in the history and appends them. Entries that
+    /// are no longer valid are kept (with updated contexts) and returned with
+    /// their (1-based) position and the reason.
+    fn replay_governments(&mut self, later : Vec<ElectionResult>) -> Vec<(usize, Error)> {
+        let mut invalidated = vec![];
+
+        for er in later {
+            let replayed = match er {
+                TopDeck(policy, _) => TopDeck(
+                    policy,
+                    self.build_next_card_context().unwrap_or_else(|error| {
+                        invalidated.push((self.governments.len() + 1, error));
+                        self.derive_next_card_context()
+                    })
+                ),
+                Election(gov) => {
+                    let rebuilt = self
+                        .build_government(
+                            gov.president,
+                            gov.chancellor,
+                            gov.president_claimed_blues,
+                            gov.chancellor_claimed_blues,
+                            gov.enacted_known.then_some(gov.policy_passed),
+                            |granted, deck_context| {
+                                self.validate_presidential_action(
+                                    granted,
+                                    gov.presidential_action,
+                                    deck_context
+                                )
+                            }
+                        )
+                        .and_then(|rebuilt| rebuilt.ok_or(Error::GameAlreadyDecided));
+                    match rebuilt {
+                        Ok(rebuilt) => Election(rebuilt),
+                        Err(error) => {
+                            invalidated.push((self.governments.len() + 1, error));
+                            let deck_context = self
+                                .build_next_card_context()
+                                .unwrap_or_else(|_| self.derive_next_card_context());
+                            Election(ElectedGovernment {
+                                presidential_action : match gov.presidential_action {
+                                    PeekAndBurn(policy, discarded, _) => PeekAndBurn(
+                                        policy,
+                                        discarded,
+                                        deck_context.atomic_draw(3, 2)
+                                    ),
+                                    action => action
+                                },
+                                deck_context,
+                                chancellor_confirmed_not_hitler : self
+                                    .count_policies_on_board(Policy::Fascist)
+                                    >= self.table_configuration.hitler_zone_passed_fascist_policies,
+                                ..gov
+                            })
+                        }
+                    }
+                }
+            };
+            let _ = self.governments.push(replayed);
         }
 
-        false
+        invalidated
     }
 
     fn collect_information(&self) -> Vec<Information> {
-        let peek_conflicts = iter_elected(&self.governments).tuple_windows().filter_map(
-            |(first, second)| match first.presidential_action {
-                TopDeckPeek(claim) => (second.president_claimed_blues
-                    != claim.iter().filter(|x| x == &&Policy::Liberal).count())
-                .then_some(Information::PolicyConflict(
-                    first.president,
-                    second.president
-                )),
-                PeekAndBurn(claim, false, _) => matches!(
-                    (second.president_claimed_blues, claim),
-                    (0, Policy::Liberal) | (3, Policy::Fascist)
-                )
-                .then_some(Information::PolicyConflict(
-                    first.president,
-                    second.president
-                )),
-                _ => None
+        self.collect_information_with_origin()
+            .into_iter()
+            .map(|(information, _origin)| information)
+            .collect()
+    }
+
+    /// All manually added and deduced information that isn't disabled
+    /// together with where it was taken from.
+    fn collect_information_with_origin(&self) -> Vec<(Information, Origin)> {
+        self.all_information_with_origin()
+            .into_iter()
+            .filter(|fact| !self.disabled_information.contains(fact))
+            .collect()
+    }
+
+    /// All manually added and deduced information including the disabled one.
+    fn all_information_with_origin(&self) -> Vec<(Information, Origin)> {
+        let indexed_elected = || {
+            self.governments
+                .iter()
+                .enumerate()
+                .filter_map(|(index, er)| match er {
+                    TopDeck(_, _) => None,
+                    Election(gov) => Some((index, gov))
+                })
+        };
+
+        let peek_conflicts = indexed_elected().tuple_windows().filter_map(
+            |((first_index, first), (second_index, second))| {
+                let origin = Origin::Peek {
+                    peeking : first_index,
+                    drawing : second_index
+                };
+                match first.presidential_action {
+                    TopDeckPeek(claim) => second
+                        .president_claimed_blues
+                        .is_some_and(|blues| {
+                            blues != claim.iter().filter(|x| x == &&Policy::Liberal).count()
+                        })
+                        .then_some((
+                            Information::PolicyConflict(first.president, second.president),
+                            origin
+                        )),
+                    PeekAndBurn(claim, false, _) => matches!(
+                        (second.president_claimed_blues, claim),
+                        (Some(0), Policy::Liberal) | (Some(3), Policy::Fascist)
+                    )
+                    .then_some((
+                        Information::PolicyConflict(first.president, second.president),
+                        origin
+                    )),
+                    _ => None
+                }
             }
         );
 
-        let immediate_conflicts = iter_elected(&self.governments).flat_map(|gov| {
+        let immediate_conflicts = indexed_elected().flat_map(|(index, gov)| {
             [
-                gov.chancellor_confirmed_not_hitler
-                    .then_some(Information::ConfirmedNotHitler(gov.chancellor)),
-                gov.conflict
-                    .then_some(Information::PolicyConflict(gov.president, gov.chancellor)),
+                gov.chancellor_confirmed_not_hitler.then_some((
+                    Information::ConfirmedNotHitler(gov.chancellor),
+                    Derivation::HitlerZoneElection
+                )),
+                gov.conflict.then_some((
+                    Information::PolicyConflict(gov.president, gov.chancellor),
+                    Derivation::PolicyConflict
+                )),
+                // a red policy requires a red card in either hand, a blue one a blue card
+                (gov.enacted_known
+                    && match gov.policy_passed {
+                        Policy::Fascist => gov.president_claimed_blues == Some(3),
+                        Policy::Liberal => gov.president_claimed_blues == Some(0)
+                    })
+                .then_some((
+                    Information::ContradictedClaim(gov.president),
+                    Derivation::EnactedPolicy
+                )),
+                (gov.enacted_known
+                    && match gov.policy_passed {
+                        Policy::Fascist => gov.chancellor_claimed_blues == Some(2),
+                        Policy::Liberal => gov.chancellor_claimed_blues == Some(0)
+                    })
+                .then_some((
+                    Information::ContradictedClaim(gov.chancellor),
+                    Derivation::EnactedPolicy
+                )),
                 match gov.presidential_action {
                     NoAction => None,
-                    Kill(dead_player) => Some(Information::ConfirmedNotHitler(dead_player)),
-                    Investigation(investigatee, Policy::Fascist) => {
-                        Some(Information::FascistInvestigation {
+                    Kill(dead_player) => Some((
+                        Information::ConfirmedNotHitler(dead_player),
+                        Derivation::Execution
+                    )),
+                    Investigation(investigatee, Policy::Fascist) => Some((
+                        Information::FascistInvestigation {
                             investigator : gov.president,
                             investigatee
-                        })
-                    },
-                    Investigation(investigatee, Policy::Liberal) => {
-                        Some(Information::LiberalInvestigation {
+                        },
+                        Derivation::Investigation
+                    )),
+                    Investigation(investigatee, Policy::Liberal) => Some((
+                        Information::LiberalInvestigation {
                             investigator : gov.president,
                             investigatee
-                        })
-                    },
-                    RevealParty(investigator, Policy::Fascist) => {
-                        Some(Information::FascistInvestigation {
+                        },
+                        Derivation::Investigation
+                    )),
+                    RevealParty(investigator, Policy::Fascist) => Some((
+                        Information::FascistInvestigation {
                             investigator,
                             investigatee : gov.president
-                        })
-                    },
-                    RevealParty(investigator, Policy::Liberal) => {
-                        Some(Information::LiberalInvestigation {
+                        },
+                        Derivation::PartyReveal
+                    )),
+                    RevealParty(investigator, Policy::Liberal) => Some((
+                        Information::LiberalInvestigation {
                             investigator,
                             investigatee : gov.president
-                        })
-                    },
+                        },
+                        Derivation::PartyReveal
+                    )),
                     // peeks are handled by windowed pre-processing
                     _ => None
                 }
             ]
             .into_iter()
             .flatten()
+            .map(move |(information, derivation)| {
+                (information, Origin::Government(index, derivation))
+            })
         });
 
-        let shuffles = self.shuffle_election_results();
-
-        let card_count_deductions = shuffles.iter().filter_map(|sa| {
-            let seen_blues = sa.total_seen_blues();
-            let governments = sa.election_results.iter().filter_map(|er| match er {
-                TopDeck(_, _) => None,
-                Election(eg) => Some(eg)
-            });
-            if seen_blues + sa.total_leftover < sa.initial_deck_liberal {
-                Some(Information::AtLeastOneFascist(
-                    governments
-                        .filter_map(|eg| (eg.president_claimed_blues < 3).then_some(eg.president))
-                        .collect()
-                ))
-            }
-            else if seen_blues > sa.initial_deck_liberal {
-                Some(Information::AtLeastOneFascist(
-                    governments
-                        .filter_map(|eg| (eg.president_claimed_blues > 0).then_some(eg.president))
-                        .collect()
-                ))
-            }
-            else {
-                None
-            }
-        });
+        let card_count_deductions = liars::card_count_deductions(self);
 
         immediate_conflicts
             .chain(peek_conflicts)
             .chain(card_count_deductions)
-            .chain(self.available_information.iter().cloned())
+            .chain(
+                self.available_information
+                    .iter()
+                    .cloned()
+                    .enumerate()
+                    .map(|(index, information)| (information, Origin::ManualFact(index)))
+            )
             .collect()
     }
 
@@ -329,10 +628,7 @@ impl PlayerState {
 
         self.governments
             .iter()
-            .group_by(|er| match er {
-                TopDeck(_, cc) => cc.shuffle_index,
-                Election(gov) => gov.deck_context.shuffle_index
-            })
+            .group_by(|er| er.card_context().shuffle_index)
             .into_iter()
             .scan(
                 (
@@ -366,15 +662,31 @@ impl PlayerState {
             .collect()
     }
 
-    fn build_next_card_context(&self) -> CardContext {
-        if let Some(latest) = self.governments.last() {
-            match latest {
-                TopDeck(_, ctxt) => ctxt.atomic_draw(1, 0),
-                Election(gov) => match gov.presidential_action {
-                    PeekAndBurn(_, true, ctxt) => ctxt.atomic_draw(1, 1),
-                    _ => gov.deck_context.atomic_draw(3, 2)
-                }
+    /// The context of the latest draw together with the amount of cards
+    /// drawn and discarded by it.
+    fn latest_draw(&self) -> Option<(CardContext, usize, usize)> {
+        self.governments.last().map(|latest| match latest {
+            TopDeck(_, ctxt) => (*ctxt, 1, 0),
+            Election(gov) => match gov.presidential_action {
+                PeekAndBurn(_, true, ctxt) => (ctxt, 1, 1),
+                _ => (gov.deck_context, 3, 2)
             }
+        })
+    }
+
+    fn build_next_card_context(&self) -> Result<CardContext> {
+        let derived = self.derive_next_card_context();
+        match self.deck_count_assertions.get(&self.governments.len()) {
+            Some(observed) => self.correct_card_context(derived, *observed),
+            None => Ok(derived)
+        }
+    }
+
+    /// The context of the next draw as tracked from the governments alone,
+    /// ignoring any observed deck size.
+    fn derive_next_card_context(&self) -> CardContext {
+        if let Some((ctxt, draw_count, discard_count)) = self.latest_draw() {
+            ctxt.atomic_draw(draw_count, discard_count)
         }
         else {
             CardContext {
@@ -385,6 +697,58 @@ impl PlayerState {
             }
         }
     }
+
+    /// Overrides the derived context with an observed deck size. All cards not
+    /// on the board are either in the deck or in the discard pile and only a
+    /// deck holding all of them got reshuffled since the latest draw.
+    fn correct_card_context(&self, derived : CardContext, observed : usize) -> Result<CardContext> {
+        let cards_in_circulation = (self.table_configuration.initial_fascist_deck_policies
+            + self.table_configuration.initial_liberal_deck_policies)
+            .saturating_sub(self.governments.len());
+
+        if observed > cards_in_circulation {
+            return Err(Error::ObservedDeckExceedsCirculation {
+                observed,
+                circulation : cards_in_circulation
+            });
+        }
+
+        let shuffle_index = match self.latest_draw() {
+            None => derived.shuffle_index,
+            Some((ctxt, _, _)) if observed == cards_in_circulation => ctxt.shuffle_index + 1,
+            Some((ctxt, _, _)) => ctxt.shuffle_index
+        };
+
+        Ok(CardContext {
+            cards_left : observed,
+            cards_discarded : cards_in_circulation - observed,
+            shuffle_index
+        })
+    }
+
+    /// Keeps the deck count assertions attached to the same governments after
+    /// one got inserted or removed at the (0-based) `position`.
+    fn shift_deck_count_assertions(&mut self, position : usize, inserted : bool) {
+        self.deck_count_assertions = std::mem::take(&mut self.deck_count_assertions)
+            .into_iter()
+            .map(|(key, observed)| match key {
+                key if key < position => (key, observed),
+                key if inserted => (key + 1, observed),
+                key => (key.saturating_sub(1), observed)
+            })
+            .collect();
+    }
+
+    /// Keeps the disabled information pointing at the same governments after
+    /// the government with the given (0-based) index got inserted or deleted.
+    fn shift_disabled_information(&mut self, government : usize, inserted : bool) {
+        self.disabled_information = std::mem::take(&mut self.disabled_information)
+            .into_iter()
+            .filter_map(|(information, origin)| {
+                Some((information, origin.shift_government(government, inserted)?))
+            })
+            .collect();
+    }
 }
 
 struct ShuffleAnalysis<'a> {
@@ -392,13 +756,13 @@ struct ShuffleAnalysis<'a> {
     election_results : Vec<&'a ElectionResult>,
     initial_deck_fascist : usize,
     initial_deck_liberal : usize,
-    #[allow(dead_code)]
     total_discarded : usize,
     total_leftover : usize
 }
 
 impl ShuffleAnalysis<'_> {
-    fn total_seen_blues(&self) -> usize {
+    /// `None` if any of the claims is unknown.
+    fn total_seen_blues(&self) -> Option<usize> {
         self.election_results.iter().map(|er| er.seen_blues()).sum()
     }
 }
@@ -482,6 +846,25 @@ fn parse_player_name(
     }
 }
 
+/// Parses a comma or whitespace separated list of distinct existing players.
+fn parse_player_set(input : &str, player_info : &PlayerInfos) -> Result<Vec<PlayerID>> {
+    let mut players = vec![];
+    for name in input
+        .split(|c : char| c == ',' || c.is_whitespace())
+        .filter(|name| !name.is_empty())
+    {
+        let player = parse_player_name(name, player_info)?;
+        player_info.player_exists(player)?;
+        if !players.contains(&player) {
+            players.push(player);
+        }
+    }
+    if players.is_empty() {
+        return Err(Error::EmptyPlayerSet);
+    }
+    Ok(players)
+}
+
 fn validate_non_dead(
     killed_player : usize,
     governments : &CallBackVec<ElectionResult>,
@@ -513,6 +896,20 @@ pub(crate) enum PresidentialAction {
 
 use PresidentialAction::*;
 
+impl PresidentialAction {
+    pub(crate) fn description(&self) -> &'static str {
+        match self {
+            NoAction => "no action",
+            Kill(_) => "a kill",
+            Investigation(_, _) => "an investigation",
+            RevealParty(_, _) => "a party reveal",
+            TopDeckPeek(_) => "a top deck peek",
+            SpecialElection(_) => "a special election",
+            PeekAndBurn(_, _, _) => "a peek and burn"
+        }
+    }
+}
+
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub(crate) enum ElectionResult {
     TopDeck(Policy, CardContext),
@@ -537,17 +934,25 @@ impl ElectionResult {
         }
     }
 
-    pub(crate) fn seen_blues(&self) -> usize {
+    /// `None` if the president didn't claim.
+    pub(crate) fn seen_blues(&self) -> Option<usize> {
         match self {
-            TopDeck(Policy::Liberal, _) => 1,
-            Election(gov) => {
-                gov.president_claimed_blues
+            TopDeck(Policy::Liberal, _) => Some(1),
+            Election(gov) => gov.president_claimed_blues.map(|blues| {
+                blues
                     + match gov.presidential_action {
                         PeekAndBurn(Policy::Liberal, true, _) => 1,
                         _ => 0
                     }
-            },
-            _ => 0
+            }),
+            _ => Some(0)
+        }
+    }
+
+    pub(crate) fn card_context(&self) -> CardContext {
+        match self {
+            TopDeck(_, cc) => *cc,
+            Election(gov) => gov.deck_context
         }
     }
 
@@ -584,10 +989,13 @@ use ElectionResult::*;
 pub(crate) struct ElectedGovernment {
     pub president : PlayerID,
     pub chancellor : PlayerID,
-    pub president_claimed_blues : usize,
-    pub chancellor_claimed_blues : usize,
+    /// `None` if the claim is unknown
+    pub president_claimed_blues : Option<usize>,
+    pub chancellor_claimed_blues : Option<usize>,
     pub conflict : bool,
     policy_passed : Policy,
+    /// whether `policy_passed` was entered rather than inferred from the claims
+    enacted_known : bool,
     presidential_action : PresidentialAction,
     deck_context : CardContext,
     chancellor_confirmed_not_hitler : bool /* first president then chancellor votes
@@ -635,9 +1043,9 @@ impl PlayerFormatable for ElectedGovernment {
         format!(
             "President {} (claim: {}) and chancellor {} (claim: {}{}) passed a {} policy{} {}",
             player_info.format_name(self.president),
-            generate_claim_pattern_from_blues(self.president_claimed_blues, 3),
+            format_claim(self.president_claimed_blues, 3),
             player_info.format_name(self.chancellor),
-            generate_claim_pattern_from_blues(self.chancellor_claimed_blues, 2),
+            format_claim(self.chancellor_claimed_blues, 2),
             if self.chancellor_confirmed_not_hitler {
                 "; confirmed not Hitler now"
             }
@@ -683,7 +1091,11 @@ pub(crate) fn standard_game(
     let table_size : usize = args["player_count"].convert()?;
     let rebalanced : bool = args["rebalance"].convert()?;
 
-    *player_state = PlayerState::new(GameConfiguration::new_standard(table_size, rebalanced)?);
+    let configuration = GameConfiguration::new_standard(table_size, rebalanced)?;
+    context
+        .history
+        .record(player_state, "set up a standard game");
+    *player_state = PlayerState::new(configuration);
 
     let num_reg_fasc = player_state.table_configuration.num_regular_fascists;
 
@@ -744,24 +1156,208 @@ pub(crate) fn show_known_facts(
     _args : HashMap<String, Value>,
     context : &mut Context
 ) -> Result<Option<String>> {
+    let player_state = &context.player_state;
+    let reads = if player_state.soft_reads.is_empty() {
+        String::new()
+    }
+    else {
+        format!(
+            "\nSoft reads weighing the role assignments:\n{}",
+            format_soft_reads(player_state)
+        )
+    };
     Ok(Some(format!(
-        "Manually added and deduced information:\n{}",
-        context
-            .player_state
-            .collect_information()
-            .iter()
+        "Manually added and deduced information with its origin:\n{}{reads}",
+        player_state
+            .all_information_with_origin()
+            .into_iter()
             .enumerate()
-            .map(|(index, information)| {
+            .map(|(index, fact)| {
                 format!(
-                    "{}. {}",
+                    "{}. {} (from {}){}",
                     index + 1,
-                    information.format(&context.player_state.player_info)
+                    fact.0.format(&player_state.player_info),
+                    fact.1,
+                    if player_state.disabled_information.contains(&fact) {
+                        " [disabled]"
+                    }
+                    else {
+                        ""
+                    }
                 )
             })
             .join("\n")
     )))
 }
 
+fn format_soft_reads(player_state : &PlayerState) -> String {
+    player_state
+        .soft_reads
+        .iter()
+        .enumerate()
+        .map(|(index, read)| {
+            format!(
+                "{}. {}{}",
+                index + 1,
+                read.format(&player_state.player_info),
+                if read.enabled { "" } else { " [disabled]" }
+            )
+        })
+        .join("\n")
+}
+
+#[debug_invariant(context.invariant())]
+pub(crate) fn add_soft_read(
+    args : HashMap<String, Value>,
+    context : &mut Context
+) -> Result<Option<String>> {
+    let player_state = &mut context.player_state;
+    let player : String = args["player"].convert()?;
+    let player = parse_player_name(&player, &player_state.player_info)?;
+    player_state.player_info.player_exists(player)?;
+    let claim : String = args["role"].convert()?;
+    let claim : Claim = claim.parse()?;
+    let confidence : String = args["confidence"].convert()?;
+    let confidence = confidence
+        .trim_end_matches('%')
+        .parse::<usize>()
+        .ok()
+        .filter(|confidence| (1..100).contains(confidence))
+        .ok_or(Error::BadConfidence(confidence))?;
+
+    let read = SoftRead {
+        player,
+        claim,
+        confidence,
+        enabled : true
+    };
+    let text = read.format(&player_state.player_info);
+
+    context.history.record(player_state, "add a soft read");
+    player_state.soft_reads.push(read);
+    player_state.available_information.callback()(player_state, true)?;
+
+    Ok(Some(format!(
+        "Successfully added the read \"{text}\" as read #{}.",
+        player_state.soft_reads.len()
+    )))
+}
+
+#[debug_invariant(context.invariant())]
+pub(crate) fn toggle_read(
+    args : HashMap<String, Value>,
+    context : &mut Context
+) -> Result<Option<String>> {
+    let position : usize = args["read"].convert()?;
+    if position == 0 || position > context.player_state.soft_reads.len() {
+        return Err(Error::BadReadIndex(position));
+    }
+
+    context
+        .history
+        .record(&context.player_state, format!("toggle read #{position}"));
+    let read = &mut context.player_state.soft_reads[position - 1];
+    read.enabled = !read.enabled;
+    let enabled = read.enabled;
+    context.player_state.available_information.callback()(&context.player_state, true)?;
+
+    Ok(Some(format!(
+        "Successfully {} read #{position}.",
+        if enabled { "enabled" } else { "disabled" }
+    )))
+}
+
+#[debug_invariant(context.invariant())]
+pub(crate) fn remove_read(
+    args : HashMap<String, Value>,
+    context : &mut Context
+) -> Result<Option<String>> {
+    let position : usize = args["read"].convert()?;
+    if position == 0 || position > context.player_state.soft_reads.len() {
+        return Err(Error::BadReadIndex(position));
+    }
+
+    context
+        .history
+        .record(&context.player_state, format!("remove read #{position}"));
+    context.player_state.soft_reads.remove(position - 1);
+    context.player_state.available_information.callback()(&context.player_state, true)?;
+
+    Ok(Some(format!("Successfully removed read #{position}.")))
+}
+
+#[debug_invariant(context.invariant())]
+pub(crate) fn disable_fact(
+    args : HashMap<String, Value>,
+    context : &mut Context
+) -> Result<Option<String>> {
+    toggle_known_fact(args, context, true)
+}
+
+#[debug_invariant(context.invariant())]
+pub(crate) fn enable_fact(
+    args : HashMap<String, Value>,
+    context : &mut Context
+) -> Result<Option<String>> {
+    toggle_known_fact(args, context, false)
+}
+
+/// Disables or re-enables the entry of `known_facts` with the given index.
+fn toggle_known_fact(
+    args : HashMap<String, Value>,
+    context : &mut Context,
+    disable : bool
+) -> Result<Option<String>> {
+    let position : usize = args["known_fact"].convert()?;
+    let fact = position
+        .checked_sub(1)
+        .and_then(|index| {
+            context
+                .player_state
+                .all_information_with_origin()
+                .into_iter()
+                .nth(index)
+        })
+        .ok_or(Error::BadFactIndex(position))?;
+    let described = format!(
+        "\"{}\" from {}",
+        fact.0.format(&context.player_state.player_info),
+        fact.1
+    );
+
+    let disabled = &context.player_state.disabled_information;
+    if disabled.contains(&fact) == disable {
+        return Ok(Some(format!(
+            "The fact {described} is already {}.",
+            if disable { "disabled" } else { "enabled" }
+        )));
+    }
+
+    if disable {
+        context.history.record(
+            &context.player_state,
+            format!("disable known fact #{position}")
+        );
+        context.player_state.disabled_information.push(fact);
+    }
+    else {
+        context.history.record(
+            &context.player_state,
+            format!("enable known fact #{position}")
+        );
+        context
+            .player_state
+            .disabled_information
+            .retain(|disabled| disabled != &fact);
+    }
+    context.player_state.available_information.callback()(&context.player_state, true)?;
+
+    Ok(Some(format!(
+        "Successfully {} the fact {described}.",
+        if disable { "disabled" } else { "enabled" }
+    )))
+}
+
 #[debug_invariant(context.invariant())]
 pub(crate) fn show_governments(
     _args : HashMap<String, Value>,
@@ -797,6 +1393,7 @@ pub(crate) fn add_hard_fact(
 
     player_state.player_info.player_exists(factual_position)?;
 
+    context.history.record(player_state, "add a hard fact");
     player_state
         .available_information
         .push(Information::HardFact(factual_position, factual_role))(player_state, true)?;
@@ -822,6 +1419,7 @@ pub(crate) fn add_conflict(
     player_state.player_info.player_exists(president)?;
     player_state.player_info.player_exists(president)?;
 
+    context.history.record(player_state, "add a conflict");
     player_state
         .available_information
         .push(Information::PolicyConflict(president, chancellor))(player_state, true)?;
@@ -847,6 +1445,9 @@ pub(crate) fn liberal_investigation(
     player_state.player_info.player_exists(investigator)?;
     player_state.player_info.player_exists(investigatee)?;
 
+    context
+        .history
+        .record(player_state, "add a liberal investigation");
     player_state
         .available_information
         .push(Information::LiberalInvestigation {
@@ -875,6 +1476,9 @@ pub(crate) fn fascist_investigation(
     player_state.player_info.player_exists(investigator)?;
     player_state.player_info.player_exists(investigatee)?;
 
+    context
+        .history
+        .record(player_state, "add a fascist investigation");
     player_state
         .available_information
         .push(Information::FascistInvestigation {
@@ -900,6 +1504,9 @@ pub(crate) fn confirm_not_hitler(
 
     player_state.player_info.player_exists(player)?;
 
+    context
+        .history
+        .record(player_state, "confirm a player not to be Hitler");
     player_state
         .available_information
         .push(Information::ConfirmedNotHitler(player))(player_state, true)?;
@@ -911,41 +1518,193 @@ pub(crate) fn confirm_not_hitler(
 }
 
 #[debug_invariant(context.invariant())]
-pub(crate) fn remove_fact(
+pub(crate) fn same_team(
     args : HashMap<String, Value>,
     context : &mut Context
 ) -> Result<Option<String>> {
-    let factual_position : usize = args["fact_to_be_removed"].convert()?;
-
-    if factual_position > context.player_state.available_information.len() || factual_position == 0
-    {
-        return Err(Error::BadFactIndex(factual_position));
-    }
-
-    context
-        .player_state
-        .available_information
-        .remove(factual_position - 1)
-        .ok_or(Error::BadFactIndex(factual_position))?(&context.player_state, true)?;
-
-    Ok(Some(format!(
-        "Successfully removed the fact #{factual_position} from the database."
-    )))
+    add_team_relation(args, context, true)
 }
 
 #[debug_invariant(context.invariant())]
-pub(crate) fn debug_filtered_roles(
+pub(crate) fn opposite_teams(
     args : HashMap<String, Value>,
     context : &mut Context
 ) -> Result<Option<String>> {
-    let mut player_state = &mut context.player_state;
-    let filtered_assignments = filter_assigned_roles(parse_filter_args(args)?, player_state, &[])?;
+    add_team_relation(args, context, false)
+}
 
-    Ok(Some(
-        filtered_assignments
-            .into_iter()
-            .map(|vpol| {
-                vpol.iter()
+fn add_team_relation(
+    args : HashMap<String, Value>,
+    context : &mut Context,
+    same : bool
+) -> Result<Option<String>> {
+    let player_state = &mut context.player_state;
+    let first : String = args["first"].convert()?;
+    let first = parse_player_name(&first, &player_state.player_info)?;
+    let second : String = args["second"].convert()?;
+    let second = parse_player_name(&second, &player_state.player_info)?;
+
+    player_state.player_info.player_exists(first)?;
+    player_state.player_info.player_exists(second)?;
+
+    let information = if same {
+        Information::SameTeam(first, second)
+    }
+    else {
+        Information::OppositeTeams(first, second)
+    };
+    let text = information.format(&player_state.player_info);
+
+    context.history.record(
+        player_state,
+        if same {
+            "add a same team relation"
+        }
+        else {
+            "add an opposite teams relation"
+        }
+    );
+    player_state.available_information.push(information)(player_state, true)?;
+
+    Ok(Some(format!(
+        "Successfully added the information \"{text}\" to the fact database."
+    )))
+}
+
+#[debug_invariant(context.invariant())]
+pub(crate) fn exactly_fascists(
+    args : HashMap<String, Value>,
+    context : &mut Context
+) -> Result<Option<String>> {
+    let count : usize = args["count"].convert()?;
+    add_player_set_fact(args, context, |players| {
+        if count > players.len() {
+            return Err(Error::FascistCountExceedsPlayers {
+                count,
+                players : players.len()
+            });
+        }
+        Ok(Information::ExactlyKFascists(players, count))
+    })
+}
+
+#[debug_invariant(context.invariant())]
+pub(crate) fn at_most_fascists(
+    args : HashMap<String, Value>,
+    context : &mut Context
+) -> Result<Option<String>> {
+    let count : usize = args["count"].convert()?;
+    add_player_set_fact(args, context, |players| {
+        Ok(Information::AtMostKFascists(players, count))
+    })
+}
+
+#[debug_invariant(context.invariant())]
+pub(crate) fn hitler_among(
+    args : HashMap<String, Value>,
+    context : &mut Context
+) -> Result<Option<String>> {
+    add_player_set_fact(args, context, |players| {
+        Ok(Information::HitlerAmong(players))
+    })
+}
+
+#[debug_invariant(context.invariant())]
+pub(crate) fn add_custom_fact(
+    args : HashMap<String, Value>,
+    context : &mut Context
+) -> Result<Option<String>> {
+    let player_state = &mut context.player_state;
+    let expression : String = args["expression"].convert()?;
+    let player_info = &player_state.player_info;
+    let expression = parse_expression(&expression, &|name| {
+        let player = parse_player_name(name, player_info)?;
+        player_info.player_exists(player)?;
+        Ok(player)
+    })?;
+    let information = Information::Custom(expression);
+    let text = information.format(&player_state.player_info);
+
+    context.history.record(player_state, "add a custom fact");
+    player_state.available_information.push(information)(player_state, true)?;
+
+    Ok(Some(format!(
+        "Successfully added the information \"{text}\" to the fact database."
+    )))
+}
+
+fn add_player_set_fact(
+    args : HashMap<String, Value>,
+    context : &mut Context,
+    fact : impl FnOnce(Vec<PlayerID>) -> Result<Information>
+) -> Result<Option<String>> {
+    let player_state = &mut context.player_state;
+    let players : String = args["players"].convert()?;
+    let information = fact(parse_player_set(&players, &player_state.player_info)?)?;
+    let text = information.format(&player_state.player_info);
+
+    context
+        .history
+        .record(player_state, "add a constraint on a set of players");
+    player_state.available_information.push(information)(player_state, true)?;
+
+    Ok(Some(format!(
+        "Successfully added the information \"{text}\" to the fact database."
+    )))
+}
+
+#[debug_invariant(context.invariant())]
+pub(crate) fn remove_fact(
+    args : HashMap<String, Value>,
+    context : &mut Context
+) -> Result<Option<String>> {
+    let factual_position : usize = args["fact_to_be_removed"].convert()?;
+
+    if factual_position > context.player_state.available_information.len() || factual_position == 0
+    {
+        return Err(Error::BadFactIndex(factual_position));
+    }
+
+    context.history.record(
+        &context.player_state,
+        format!("remove fact #{factual_position}")
+    );
+    let callback = context
+        .player_state
+        .available_information
+        .remove(factual_position - 1)
+        .ok_or(Error::BadFactIndex(factual_position))?;
+    context.player_state.disabled_information =
+        std::mem::take(&mut context.player_state.disabled_information)
+            .into_iter()
+            .filter_map(|(information, origin)| match origin {
+                Origin::ManualFact(index) if index + 1 == factual_position => None,
+                Origin::ManualFact(index) if index + 1 > factual_position => {
+                    Some((information, Origin::ManualFact(index - 1)))
+                },
+                origin => Some((information, origin))
+            })
+            .collect();
+    callback(&context.player_state, true)?;
+
+    Ok(Some(format!(
+        "Successfully removed the fact #{factual_position} from the database."
+    )))
+}
+
+#[debug_invariant(context.invariant())]
+pub(crate) fn debug_filtered_roles(
+    args : HashMap<String, Value>,
+    context : &mut Context
+) -> Result<Option<String>> {
+    let mut player_state = &mut context.player_state;
+    let filtered_assignments = filter_assigned_roles(parse_filter_args(args)?, player_state, &[])?;
+
+    Ok(Some(
+        filtered_assignments
+            .into_iter()
+            .map(|vpol| {
+                vpol.iter()
                     .map(|(pos, role)| {
                         format!("({}: {})", player_state.player_info.format_name(*pos), role)
                     })
@@ -960,10 +1719,18 @@ pub(crate) fn impossible_teams(
     args : HashMap<String, Value>,
     context : &mut Context
 ) -> Result<Option<String>> {
-    let player_state = &mut context.player_state;
+    impossible_teams_report(parse_filter_args(args)?, &context.player_state, &[]).map(Some)
+}
+
+fn impossible_teams_report(
+    filter_args : (bool, bool),
+    player_state : &PlayerState,
+    temporary_infomration : &[Information]
+) -> Result<String> {
     let num_fascists = player_state.table_configuration.num_regular_fascists + 1;
 
-    let filtered_assignments = filter_assigned_roles(parse_filter_args(args)?, player_state, &[])?;
+    let filtered_assignments =
+        filter_assigned_roles(filter_args, player_state, temporary_infomration)?;
 
     let legal_fascist_positions = filtered_assignments
         .into_iter()
@@ -995,8 +1762,8 @@ pub(crate) fn impossible_teams(
         impossible_teams.append(&mut local_impossible);
     }
 
-    Ok(Some(
-        impossible_teams
+    Ok(perspective_note(player_state)
+        + &impossible_teams
             .into_iter()
             .map(|vfas| {
                 (
@@ -1014,20 +1781,223 @@ pub(crate) fn impossible_teams(
                     format!("{s} can't be a fascist.")
                 }
             })
-            .join("\n")
+            .join("\n"))
+}
+
+/// Marks analyses which only hold from the user's own perspective.
+fn perspective_note(player_state : &PlayerState) -> String {
+    player_state
+        .perspective
+        .as_ref()
+        .map_or_else(String::new, |perspective| {
+            format!(
+                "From the perspective of {}:\n",
+                perspective.format(&player_state.player_info)
+            )
+        })
+}
+
+#[debug_invariant(context.invariant())]
+pub(crate) fn whoami(
+    args : HashMap<String, Value>,
+    context : &mut Context
+) -> Result<Option<String>> {
+    let player_state = &context.player_state;
+    let seat : String = args["seat"].convert()?;
+    let seat = parse_player_name(&seat, &player_state.player_info)?;
+    player_state.player_info.player_exists(seat)?;
+    let role : String = args["role"].convert()?;
+    let role : SecretRole = role.parse()?;
+    let teammates : String = args["teammates"].convert()?;
+    let perspective = Perspective::new(
+        seat,
+        role,
+        &teammates,
+        &player_state.player_info,
+        &player_state.table_configuration
+    )?;
+
+    let mut staged = player_state.clone();
+    staged.perspective = Some(perspective);
+    // the most permissive filter, nothing survives a stricter one either
+    match filter_assigned_roles((true, true), &staged, &[]) {
+        Err(Error::LogicalInconsistency) => {
+            return Err(Error::BadPerspective(
+                "no role assignment matching it is consistent with the known facts"
+            ));
+        },
+        result => result?
+    };
+    let num_assignments = staged.current_roles().len();
+    let text = perspective_note(&staged);
+
+    context
+        .history
+        .record(&context.player_state, "set the own perspective");
+    context.player_state.perspective = staged.perspective;
+    context.player_state.available_information.callback()(&context.player_state, true)?;
+
+    Ok(Some(format!(
+        "{text}{num_assignments} role assignments remain possible."
+    )))
+}
+
+#[debug_invariant(context.invariant())]
+pub(crate) fn plan_claim(
+    args : HashMap<String, Value>,
+    context : &mut Context
+) -> Result<Option<String>> {
+    let player_state = &context.player_state;
+    let chancellor : String = args["chancellor"].convert()?;
+    let chancellor = parse_player_name(&chancellor, &player_state.player_info)?;
+    let drawn : String = args["drawn"].convert()?;
+    let (drawn_blues, _, _) = parse_pattern(drawn, 3, 3)?;
+    let discarded : String = args["discarded"].convert()?;
+    let discarded : Policy = discarded.parse()?;
+
+    let (chancellor_liberal, ratings) = planning::plan_claims(
+        parse_filter_args(args)?,
+        player_state,
+        chancellor,
+        drawn_blues,
+        discarded
+    )?;
+
+    let percent = |p : f64| format!("{:.1}%", p * 100.0);
+    Ok(Some(
+        std::iter::once(format!(
+            "{}Player {} is a liberal with {} chance and then claims the truth.",
+            perspective_note(player_state),
+            player_state.player_info.format_name(chancellor),
+            percent(chancellor_liberal)
+        ))
+        .chain(ratings.into_iter().map(|rating| {
+            let standings = match rating.standings {
+                None => "\n  The public would see a contradiction.".to_owned(),
+                Some(standings) => standings
+                    .into_iter()
+                    .map(|standing| {
+                        format!(
+                            "\n  Player {}: liberal {} -> {}, Hitler {} -> {}",
+                            player_state.player_info.format_name(standing.player),
+                            percent(standing.liberal.0),
+                            percent(standing.liberal.1),
+                            percent(standing.hitler.0),
+                            percent(standing.hitler.1)
+                        )
+                    })
+                    .join("")
+            };
+            format!(
+                "Claiming {}: {} of the hands from the public deck look like this, {} chance of a \
+                 conflict.{standings}",
+                generate_claim_pattern_from_blues(rating.claimed_blues, 3),
+                percent(rating.believability),
+                percent(rating.conflict)
+            )
+        }))
+        .join("\n")
     ))
 }
 
 #[debug_invariant(context.invariant())]
-pub(crate) fn hitler_snipe(
+pub(crate) fn spectator(
+    _args : HashMap<String, Value>,
+    context : &mut Context
+) -> Result<Option<String>> {
+    if context.player_state.perspective.is_none() {
+        return Ok(Some("Already analysing as a spectator.".to_owned()));
+    }
+
+    context
+        .history
+        .record(&context.player_state, "drop the own perspective");
+    context.player_state.perspective = None;
+    context.player_state.available_information.callback()(&context.player_state, true)?;
+
+    Ok(Some(
+        "Successfully dropped the own perspective, analysing as a spectator again.".to_owned()
+    ))
+}
+
+#[debug_invariant(context.invariant())]
+pub(crate) fn upcoming(
     args : HashMap<String, Value>,
     context : &mut Context
 ) -> Result<Option<String>> {
-    let mut player_state = &mut context.player_state;
+    let player_state = &context.player_state;
+    let count : usize = args["count"].convert()?;
     let histogram = filtered_histogramm(parse_filter_args(args)?, player_state, &[])?;
 
-    Ok(Some(
-        histogram
+    let presidents = player_state.next_presidents(count.max(1));
+    let president = presidents[0];
+    let chancellors = player_state
+        .player_info
+        .keys()
+        .copied()
+        .filter(|pid| *pid != president)
+        .filter(|pid| {
+            player_state
+                .player_interactable(*pid, &player_state.player_info)
+                .is_ok()
+        })
+        .filter(|pid| player_state.is_eligible_chancellor(*pid))
+        .map(|pid| {
+            let hitler = histogram
+                .get(&pid)
+                .map(|(roles, total)| {
+                    roles
+                        .get(&SecretRole::Hitler)
+                        .copied()
+                        .unwrap_or(FilterResult::none(*total))
+                })
+                .unwrap_or(FilterResult::none(0));
+            format!(
+                "Player {}: {} chance of being a fascist, {hitler} chance of being Hitler.",
+                player_state.player_info.format_name(pid),
+                advice::fascist_probability(&histogram, pid)
+            )
+        })
+        .join("\n");
+    let hitler_zone = player_state.count_policies_on_board(Policy::Fascist)
+        >= player_state
+            .table_configuration
+            .hitler_zone_passed_fascist_policies;
+
+    Ok(Some(format!(
+        "{}The next presidents are {}.\nEligible chancellors for player {}{}:\n{chancellors}",
+        perspective_note(player_state),
+        presidents
+            .iter()
+            .map(|pid| player_state.player_info.format_name(*pid))
+            .join(", "),
+        player_state.player_info.format_name(president),
+        if hitler_zone {
+            " (electing Hitler wins the game for the fascists)"
+        }
+        else {
+            ""
+        }
+    )))
+}
+
+#[debug_invariant(context.invariant())]
+pub(crate) fn hitler_snipe(
+    args : HashMap<String, Value>,
+    context : &mut Context
+) -> Result<Option<String>> {
+    hitler_snipe_report(parse_filter_args(args)?, &context.player_state, &[]).map(Some)
+}
+
+fn hitler_snipe_report(
+    filter_args : (bool, bool),
+    player_state : &PlayerState,
+    temporary_infomration : &[Information]
+) -> Result<String> {
+    let histogram = filtered_histogramm(filter_args, player_state, temporary_infomration)?;
+
+    Ok(perspective_note(player_state)
+        + &histogram
             .iter()
             .map(|(pid, (roles, total))| {
                 (
@@ -1047,52 +2017,334 @@ pub(crate) fn hitler_snipe(
                     player_state.player_info.format_name(*pid),
                 )
             })
+            .join("\n"))
+}
+
+#[debug_invariant(context.invariant())]
+pub(crate) fn investigation_advice(
+    args : HashMap<String, Value>,
+    context : &mut Context
+) -> Result<Option<String>> {
+    let player_state = &context.player_state;
+    let investigator : String = args["investigator"].convert()?;
+    let investigator = parse_player_name(&investigator, &player_state.player_info)?;
+    player_state.player_interactable(investigator, &player_state.player_info)?;
+
+    let (investigator_fascist, ratings) =
+        advice::rate_investigations(parse_filter_args(args)?, player_state, investigator)?;
+
+    Ok(Some(
+        perspective_note(player_state)
+            + &std::iter::once(format!(
+                "Player {} has a {investigator_fascist} chance of being a fascist, their result \
+                 can't be trusted in that case.",
+                player_state.player_info.format_name(investigator)
+            ))
+            .chain(ratings.iter().enumerate().map(|(index, rating)| {
+                format!(
+                    "{}. Player {}: expected to remove {:.3} bits of uncertainty, {:.1}% chance \
+                     of a liberal result.",
+                    index + 1,
+                    player_state.player_info.format_name(rating.investigatee),
+                    rating.expected_entropy_reduction,
+                    rating.liberal_result_probability * 100.0
+                )
+            }))
             .join("\n")
     ))
 }
 
 #[debug_invariant(context.invariant())]
-pub(crate) fn liberal_percent(
+pub(crate) fn advise(
     args : HashMap<String, Value>,
     context : &mut Context
 ) -> Result<Option<String>> {
-    let mut player_state = &mut context.player_state;
-    let histogram = filtered_histogramm(parse_filter_args(args)?, player_state, &[])?;
+    let player_state = &context.player_state;
+    let power : String = args["power"].convert()?;
+    let kill = match power.to_lowercase().as_str() {
+        "kill" | "shoot" => true,
+        "se" | "special_election" => false,
+        _ => return Err(Error::BadPresidentialPower(power))
+    };
+    let president : String = args["president"].convert()?;
+    let president = parse_player_name(&president, &player_state.player_info)?;
+    player_state.player_interactable(president, &player_state.player_info)?;
 
-    Ok(Some(
-        histogram
-            .iter()
-            .map(|(pid, (roles, total))| {
+    let ratings = advice::rate_targets(parse_filter_args(args)?, player_state, president)?;
+    // a kill wants hitler, a special election wants a liberal president
+    let ratings = if kill {
+        ratings
+            .into_iter()
+            .sorted_by_key(|rating| {
                 (
-                    pid,
-                    roles
-                        .get(&SecretRole::Liberal)
-                        .copied()
-                        .unwrap_or(FilterResult::none(*total))
+                    -(rating.hitler.num_matching as isize),
+                    rating.liberal.num_matching
                 )
             })
-            .map(|(pid, lib_count)| {
-                format!(
-                    "Player {}: {lib_count} chance of being a liberal.",
-                    player_state.player_info.format_name(*pid)
+            .collect_vec()
+    }
+    else {
+        ratings
+            .into_iter()
+            .sorted_by_key(|rating| {
+                (
+                    -(rating.liberal.num_matching as isize),
+                    rating.hitler.num_matching
                 )
             })
-            .join("\n")
+            .collect_vec()
+    };
+
+    Ok(Some(
+        perspective_note(player_state)
+            + &ratings
+                .iter()
+                .enumerate()
+                .map(|(index, rating)| {
+                    let removal = match rating.after_removal {
+                        Some((liberal_share, entropy)) if kill => format!(
+                            " If they aren't hitler, the others are {:.1}% liberal on average \
+                             with {entropy:.3} bits of uncertainty left.",
+                            liberal_share * 100.0
+                        ),
+                        None if kill => " Killing them wins the game.".to_owned(),
+                        _ => String::new()
+                    };
+                    format!(
+                        "{}. Player {}: {} chance of being Hitler, {} chance of being a \
+                         liberal.{removal}",
+                        index + 1,
+                        player_state.player_info.format_name(rating.target),
+                        rating.hitler,
+                        rating.liberal
+                    )
+                })
+                .join("\n")
     ))
 }
 
-fn generate_claim_pattern_from_blues(blues : usize, pattern_length : usize) -> String {
-    let num_reds = pattern_length - blues;
-    std::iter::repeat("R")
-        .take(num_reds)
-        .chain(std::iter::repeat("B").take(blues))
-        .join("")
+#[debug_invariant(context.invariant())]
+pub(crate) fn win_odds(
+    args : HashMap<String, Value>,
+    context : &mut Context
+) -> Result<Option<String>> {
+    let player_state = &context.player_state;
+    let liberal_strategy : String = args["liberal_strategy"].convert()?;
+    let fascist_strategy : String = args["fascist_strategy"].convert()?;
+    let strategies = (liberal_strategy.parse()?, fascist_strategy.parse()?);
+    let games : usize = args["games"].convert()?;
+    let games = games.max(1);
+
+    let endings =
+        simulation::simulate_endings(parse_filter_args(args)?, player_state, strategies, games)?;
+    let share = |count : usize| count as f64 / games as f64 * 100.0;
+    let by_kind = |kind| {
+        share(
+            endings
+                .iter()
+                .filter(|(ending, _count)| ending.kind == kind)
+                .map(|(_ending, count)| count)
+                .sum()
+        )
+    };
+    let liberal_wins = endings
+        .iter()
+        .filter(|(ending, _count)| ending.kind.liberals_win())
+        .map(|(_ending, count)| count)
+        .sum();
+
+    Ok(Some(
+        perspective_note(player_state)
+            + &[
+                format!(
+                    "Liberals win {:.1}% of {games} simulated games ({:.1}% by policies, {:.1}% \
+                     by killing Hitler).",
+                    share(liberal_wins),
+                    by_kind(simulation::EndingKind::LiberalPolicies),
+                    by_kind(simulation::EndingKind::HitlerKilled)
+                ),
+                format!(
+                    "Fascists win {:.1}% ({:.1}% by policies, {:.1}% by electing Hitler \
+                     chancellor).",
+                    share(games - liberal_wins),
+                    by_kind(simulation::EndingKind::FascistPolicies),
+                    by_kind(simulation::EndingKind::HitlerChancellor)
+                )
+            ]
+            .into_iter()
+            .chain(
+                endings
+                    .iter()
+                    .max_by_key(|(_ending, count)| **count)
+                    .map(|(ending, count)| {
+                        format!(
+                            "The most likely ending ({:.1}%) is {} with {} liberal and {} fascist \
+                             policies on the board.",
+                            share(*count),
+                            ending.kind,
+                            ending.liberal_policies,
+                            ending.fascist_policies
+                        )
+                    })
+            )
+            .join("\n")
+    ))
 }
 
-fn generate_dot_report(
-    information : &Vec<Information>,
-    governments : &[ElectionResult],
-    players : &BTreeMap<PlayerID, PlayerInfo>
+#[debug_invariant(context.invariant())]
+pub(crate) fn explain(
+    args : HashMap<String, Value>,
+    context : &mut Context
+) -> Result<Option<String>> {
+    let player_state = &context.player_state;
+    let player : String = args["player"].convert()?;
+    let player = parse_player_name(&player, &player_state.player_info)?;
+    player_state.player_info.player_exists(player)?;
+    let role : String = args["role"].convert()?;
+    let role : SecretRole = role.parse()?;
+    let filter_args = parse_filter_args(args)?;
+
+    let core = explain::minimal_core(
+        filter_args,
+        player_state,
+        player_state.collect_information_with_origin(),
+        |roles| roles.get(&player) == Some(&role)
+    );
+
+    Ok(Some(
+        perspective_note(player_state)
+            + &match core {
+                None => format!(
+                    "Nothing rules out player {} being {role}.",
+                    player_state.player_info.format_name(player)
+                ),
+                Some(core) if core.is_empty() => format!(
+                    "Player {} can't be {role} in this game configuration.",
+                    player_state.player_info.format_name(player)
+                ),
+                Some(core) => format!(
+                    "Player {} can't be {role} because of:\n{}",
+                    player_state.player_info.format_name(player),
+                    core.iter()
+                        .enumerate()
+                        .map(|(index, (information, origin))| {
+                            format!(
+                                "{}. {} (from {origin})",
+                                index + 1,
+                                information.format(&player_state.player_info)
+                            )
+                        })
+                        .join("\n")
+                )
+            }
+    ))
+}
+
+#[debug_invariant(context.invariant())]
+pub(crate) fn diagnose(
+    args : HashMap<String, Value>,
+    context : &mut Context
+) -> Result<Option<String>> {
+    let player_state = &context.player_state;
+    let Some(inconsistency) =
+        explain::diagnose_inconsistency(parse_filter_args(args)?, player_state)
+    else {
+        return Ok(Some("The fact database is consistent.".to_owned()));
+    };
+
+    let known_facts = player_state.all_information_with_origin();
+    let describe = |fact : &(Information, Origin)| {
+        let position = known_facts
+            .iter()
+            .position(|known| known == fact)
+            .map_or(0, |index| index + 1);
+        format!(
+            "#{position}: {} (from {})",
+            fact.0.format(&player_state.player_info),
+            fact.1
+        )
+    };
+
+    let core = inconsistency.core.iter().map(describe).join("\n");
+    let suggestion = if inconsistency.resolving.is_empty() {
+        "No single fact can be disabled to restore consistency, there is more than one \
+         contradiction."
+            .to_owned()
+    }
+    else {
+        format!(
+            "Disabling any one of the following facts (see disable_fact) restores consistency:\n{}",
+            inconsistency.resolving.iter().map(describe).join("\n")
+        )
+    };
+
+    Ok(Some(format!(
+        "The following known facts contradict each other:\n{core}\n{suggestion}"
+    )))
+}
+
+#[debug_invariant(context.invariant())]
+pub(crate) fn liberal_percent(
+    args : HashMap<String, Value>,
+    context : &mut Context
+) -> Result<Option<String>> {
+    liberal_percent_report(parse_filter_args(args)?, &context.player_state, &[]).map(Some)
+}
+
+fn liberal_percent_report(
+    filter_args : (bool, bool),
+    player_state : &PlayerState,
+    temporary_infomration : &[Information]
+) -> Result<String> {
+    let histogram = filtered_histogramm(filter_args, player_state, temporary_infomration)?;
+
+    Ok(perspective_note(player_state)
+        + &histogram
+            .iter()
+            .map(|(pid, (roles, total))| {
+                (
+                    pid,
+                    roles
+                        .get(&SecretRole::Liberal)
+                        .copied()
+                        .unwrap_or(FilterResult::none(*total))
+                )
+            })
+            .map(|(pid, lib_count)| {
+                format!(
+                    "Player {}: {lib_count} chance of being a liberal.",
+                    player_state.player_info.format_name(*pid)
+                )
+            })
+            .join("\n"))
+}
+
+fn generate_claim_pattern_from_blues(blues : usize, pattern_length : usize) -> String {
+    let num_reds = pattern_length - blues;
+    std::iter::repeat("R")
+        .take(num_reds)
+        .chain(std::iter::repeat("B").take(blues))
+        .join("")
+}
+
+/// Either the president claims to have passed a blue the chancellor denies or
+/// the chancellor claims more blues than the president could have passed.
+fn claims_conflict(president_blues : usize, chancellor_blues : usize) -> bool {
+    (president_blues > 0 && chancellor_blues == 0) || chancellor_blues > president_blues
+}
+
+/// Like `generate_claim_pattern_from_blues` but `?` for an unknown claim.
+fn format_claim(blues : Option<usize>, pattern_length : usize) -> String {
+    blues.map_or_else(
+        || "?".to_owned(),
+        |blues| generate_claim_pattern_from_blues(blues, pattern_length)
+    )
+}
+
+fn generate_dot_report(
+    information : &[Information],
+    governments : &[ElectionResult],
+    players : &BTreeMap<PlayerID, PlayerInfo>
 ) -> String {
     let mut node_attributes : BTreeMap<PlayerID, Vec<Information>> = BTreeMap::new();
     players.iter().for_each(|(key, _name)| {
@@ -1104,6 +2356,20 @@ fn generate_dot_report(
 
     let mut handled_conflicts = BTreeSet::new();
 
+    // constraints on sets of players get a box connected to all members
+    let mut constraint_statements = vec![];
+    let mut constraint_count = 0;
+    let mut constraint_node = |label : String, members : &[PlayerID]| {
+        let node = format!("constraint{constraint_count}");
+        constraint_count += 1;
+        constraint_statements.push(format!("{node} [shape=box,label=\"{label}\"]"));
+        constraint_statements.extend(
+            members
+                .iter()
+                .map(|pid| format!("{node} -> {pid} [dir=none,style=dotted]"))
+        );
+    };
+
     for (index, gov) in governments.iter().enumerate() {
         match gov {
             Election(gov) => {
@@ -1130,8 +2396,8 @@ fn generate_dot_report(
                     else {
                         "none"
                     },
-                    generate_claim_pattern_from_blues(gov.president_claimed_blues,3),
-                    generate_claim_pattern_from_blues(gov.chancellor_claimed_blues, 2)
+                    format_claim(gov.president_claimed_blues, 3),
+                    format_claim(gov.chancellor_claimed_blues, 2)
                 ));
                 if let Kill(killed_player) = gov.presidential_action {
                     statements.push(format!(
@@ -1165,13 +2431,34 @@ fn generate_dot_report(
                 investigator,
                 investigatee
             } => statements.push(format!("{investigator} -> {investigatee} [color=red]")),
-            Information::HardFact(pid, _) => {
+            Information::HardFact(pid, _) | Information::ContradictedClaim(pid) => {
                 node_attributes.entry(*pid).or_default().push(info.clone())
             },
+            Information::SameTeam(left, right) => statements.push(format!(
+                "{left} -> {right} [dir=none,style=dashed,color=darkgreen,label=same]"
+            )),
+            Information::OppositeTeams(left, right) => statements.push(format!(
+                "{left} -> {right} [dir=none,style=dashed,color=orange,label=opposite]"
+            )),
+            Information::ExactlyKFascists(members, count) => {
+                constraint_node(format!("exactly {count} fascist(s)"), members)
+            },
+            Information::AtMostKFascists(members, count) => {
+                constraint_node(format!("at most {count} fascist(s)"), members)
+            },
+            Information::HitlerAmong(members) => {
+                constraint_node("Hitler among".to_owned(), members)
+            },
+            Information::Custom(expression) => constraint_node(
+                expression.format(players),
+                &expression.players().into_iter().collect_vec()
+            ),
             _ => {}
         }
     }
 
+    statements.append(&mut constraint_statements);
+
     node_attributes
         .into_iter()
         .map(|(pid, vinfo)| {
@@ -1186,6 +2473,7 @@ fn generate_dot_report(
                         },
                         Information::HardFact(_pid, role) =>
                             format!("color={}", if role.is_fascist() { "red" } else { "blue" }),
+                        Information::ContradictedClaim(_) => "color=red".to_owned(),
                         _ => unreachable!()
                     })
                     .join(",")
@@ -1204,6 +2492,71 @@ enum InvocationStrategy {
     None
 }
 
+/// Writes the graph for the given information to `<filename>.dot` and, unless
+/// no dot invocation was requested, renders it to `<filename>.png` and copies
+/// the image to the clipboard.
+fn render_graph(
+    information : &[Information],
+    player_state : &PlayerState,
+    filename : &str,
+    baseline_command : &str,
+    strategy : &InvocationStrategy
+) -> Result<()> {
+    let dotfile = format!("{filename}.dot");
+    let imagefile = format!("{filename}.png");
+
+    let options = vec![
+        "-Tpng".to_string(),
+        "-o".to_string(),
+        imagefile.clone(),
+        dotfile.clone(),
+    ];
+
+    let file_content = generate_dot_report(
+        information,
+        player_state.governments.deref(),
+        &player_state.player_info
+    );
+
+    fs::write(&dotfile, file_content)?;
+
+    let mut command = Command::new(baseline_command);
+
+    match strategy {
+        InvocationStrategy::None => return Ok(()),
+        InvocationStrategy::Bash => command
+            .arg("-c")
+            .arg(format! {"\"dot\" {}", options.iter().join(" ")}),
+        InvocationStrategy::Directly => command.args(&options)
+    };
+
+    let dot_process = command
+        .stdin(Stdio::null())
+        .stderr(Stdio::piped())
+        .stdout(Stdio::piped())
+        .output()?;
+
+    if !dot_process.stdout.is_empty() {
+        return Err(Error::UnexpectedStdout(dot_process.stdout));
+    }
+    if !dot_process.stderr.is_empty() {
+        return Err(Error::UnexpectedStderr(dot_process.stderr));
+    }
+
+    let image = image::io::Reader::open(&imagefile)?.decode()?;
+    let image = image.as_rgba8().ok_or(Error::EncodingFailed)?;
+    let mut clipboard = Clipboard::new()?;
+    clipboard.set_image(ImageData {
+        width : image.width() as usize,
+        height : image.height() as usize,
+        bytes : std::borrow::Cow::Borrowed(image.as_bytes())
+    })?;
+
+    fs::remove_file(&dotfile)?;
+
+    Ok(())
+}
+
 #[debug_invariant(context.invariant())]
 pub(crate) fn graph(
     args : HashMap<String, Value>,
@@ -1215,274 +2568,983 @@ pub(crate) fn graph(
     let auto_update : bool = args["auto"].convert()?;
     let executable : String = args["dot-invocation"].convert()?;
 
-    let dotfile = format!("{filename}.dot");
-    let imagefile = format!("{filename}.png");
-
-    let options = vec![
-        "-Tpng".to_string(),
-        "-o".to_string(),
-        imagefile.clone(),
-        dotfile.clone(),
-    ];
-
     let (baseline_command, strategy) = executable_parser(executable)?;
 
     let closure : Callback = Rc::new(move |ps, auto| {
         if !auto || auto_update {
-            let file_content = generate_dot_report(
+            render_graph(
                 &ps.collect_information(),
-                ps.governments.deref(),
-                &ps.player_info
-            );
+                ps,
+                &filename,
+                &baseline_command,
+                &strategy
+            )?;
+        }
 
-            fs::write(&dotfile, file_content)?;
+        Ok(())
+    });
 
-            let mut command = Command::new(&baseline_command);
+    context
+        .player_state
+        .available_information
+        .register_callback(CallbackKind::GovernmentOverviewGraph, Rc::clone(&closure));
+    context
+        .player_state
+        .governments
+        .register_callback(CallbackKind::GovernmentOverviewGraph, Rc::clone(&closure));
+    context.player_state.governments.callback()(&context.player_state, false)?;
+
+    Ok(Some(format!(
+        "Run \"dot -Tpng -o {resp_filename}.png {resp_filename}.dot\" in a separate shell (e.g. \
+         bash, cmd, powershell, ...) in the current working directory to generate the graph."
+    )))
+}
 
+#[debug_invariant(context.invariant())]
+pub(crate) fn whatif(
+    args : HashMap<String, Value>,
+    context : &mut Context
+) -> Result<Option<String>> {
+    let player_state = &context.player_state;
+    let facts : String = args["facts"].convert()?;
+    let command : String = args["command"].convert()?;
+    let temporary_infomration = facts
+        .split([';', ','])
+        .map(str::trim)
+        .filter(|fact| !fact.is_empty())
+        .map(|fact| log_import::parse_fact(fact, &player_state.player_info))
+        .collect::<Result<Vec<_>>>()?;
+
+    // hands the generic arguments to the evaluated command in order
+    let forwarded = |required : &[&str], optional : &[&str]| -> Result<Vec<String>> {
+        let provided = ["first_argument", "second_argument", "third_argument"]
+            .iter()
+            .map(|key| args[*key].convert())
+            .collect::<std::result::Result<Vec<String>, _>>()?;
+        let expected = required.len() + optional.len();
+        if provided[expected..].iter().any(|arg| !arg.is_empty()) {
+            return Err(repl_rs::Error::TooManyArguments(command.clone(), expected).into());
+        }
+        if let Some(missing) = required
+            .iter()
+            .zip(&provided)
+            .find(|(_name, arg)| arg.is_empty())
+        {
+            return Err(repl_rs::Error::MissingRequiredArgument(
+                command.clone(),
+                missing.0.to_string()
+            )
+            .into());
+        }
+        Ok(provided[..expected].to_vec())
+    };
+    let filter_args = || -> Result<(bool, bool)> {
+        let arguments = forwarded(
+            &["allow_fascist_fascist_conflict", "allow_aggressive_hitler"],
+            &[]
+        )?;
+        Ok((
+            arguments[0].parse().map_err(repl_rs::Error::from)?,
+            arguments[1].parse().map_err(repl_rs::Error::from)?
+        ))
+    };
+
+    let report = match command.as_str() {
+        "hitler_snipe" => {
+            hitler_snipe_report(filter_args()?, player_state, &temporary_infomration)?
+        },
+        "liberal_percent" => {
+            liberal_percent_report(filter_args()?, player_state, &temporary_infomration)?
+        },
+        "impossible_teams" => {
+            impossible_teams_report(filter_args()?, player_state, &temporary_infomration)?
+        },
+        "graph" => {
+            let arguments = forwarded(&["filename"], &["dot-invocation"])?;
+            let (baseline_command, strategy) = executable_parser(arguments[1].clone())?;
+            render_graph(
+                &player_state
+                    .collect_information()
+                    .into_iter()
+                    .chain(temporary_infomration.iter().cloned())
+                    .collect_vec(),
+                player_state,
+                &arguments[0],
+                &baseline_command,
+                &strategy
+            )?;
             match strategy {
-                InvocationStrategy::None => return Ok(()),
-                InvocationStrategy::Bash => command
-                    .arg("-c")
-                    .arg(format! {"\"dot\" {}", options.iter().join(" ")}),
-                InvocationStrategy::Directly => command.args(&options)
+                InvocationStrategy::None => format!("Wrote the graph to {}.dot.", arguments[0]),
+                _ => format!(
+                    "Rendered the graph to {}.png and copied it to the clipboard.",
+                    arguments[0]
+                )
+            }
+        },
+        _ => return Err(repl_rs::Error::UnknownCommand(command).into())
+    };
+
+    Ok(Some(format!(
+        "Assuming additionally that\n{}\n\n{report}",
+        temporary_infomration
+            .iter()
+            .map(|information| information.format(&player_state.player_info))
+            .join("\n")
+    )))
+}
+
+#[debug_invariant(context.invariant())]
+pub(crate) fn name(
+    args : HashMap<String, Value>,
+    context : &mut Context
+) -> Result<Option<String>> {
+    let position : usize = args["position"].convert()?;
+    let name : String = args["display_name"].convert()?;
+
+    context.player_state.player_info.player_exists(position)?;
+
+    context
+        .history
+        .record(&context.player_state, format!("name player {position}"));
+    context
+        .player_state
+        .player_info
+        .get_mut(&position)
+        .ok_or(Error::BadPlayerID(position))?
+        .name = name.clone();
+
+    Ok(Some(format!(
+        "Successfully registered the name {name} for player {position}."
+    )))
+}
+
+/// Parses a claim of `length` policies, `None` for the unknown claim `?`.
+pub(super) fn parse_claim(pattern : &str, length : usize) -> Result<Option<usize>> {
+    if pattern == "?" {
+        return Ok(None);
+    }
+    Ok(Some(parse_pattern(pattern.to_owned(), length, length)?.0))
+}
+
+/// Parses the government arguments shared by all government-adding commands
+/// and validates them against the history tracked by `player_state`.
+fn government_from_args(
+    args : &HashMap<String, Value>,
+    player_state : &PlayerState
+) -> Result<Option<ElectedGovernment>> {
+    let president : String = args["president"].convert()?;
+    let president = parse_player_name(&president, &player_state.player_info)?;
+    let chancellor : String = args["chancellor"].convert()?;
+    let chancellor = parse_player_name(&chancellor, &player_state.player_info)?;
+    let presidential_pattern : String = args["presidential_blues"].convert()?;
+    let chancellor_pattern : String = args["chancellor_blues"].convert()?;
+
+    // the chancellor claim may be followed by the enacted policy, e.g. `rb:r`
+    let (chancellor_pattern, enacted) = match chancellor_pattern.split_once(':') {
+        Some((claim, enacted)) => (
+            claim.to_owned(),
+            Some(parse_pattern(enacted.to_owned(), 1, 1)?.2[0])
+        ),
+        None => (chancellor_pattern, None)
+    };
+
+    let president_claimed_blues = parse_claim(&presidential_pattern, 3)?;
+    let chancellor_claimed_blues = parse_claim(&chancellor_pattern, 2)?;
+
+    let retrieve_player_opt_first = || -> Result<_> {
+        let text_input : String = args["first_argument"].convert()?;
+        let extraced_player = parse_player_name(&text_input, &player_state.player_info)?;
+        player_state.player_interactable(extraced_player, &player_state.player_info)?;
+        Ok(extraced_player)
+    };
+
+    let retrieve_policy_opt_second = || -> Result<_> {
+        let text_input : String = args["second_argument"].convert()?;
+        Ok(*parse_pattern(text_input, 1, 1)?.2.first().unwrap())
+    };
+
+    let retrieve_policy_opt_first = |count| -> Result<_> {
+        let text_input : String = args["first_argument"].convert()?;
+        Ok(parse_pattern(text_input, count, count)?.2)
+    };
+
+    let retrieve_boolean_opt_second = || -> Result<_> {
+        let text_input : bool = args["second_argument"].convert()?;
+        Ok(text_input)
+    };
+
+    // arguments the granted power doesn't take have to be left out
+    let ensure_unused = |key : &str, granted : PresidentialAction| -> Result<()> {
+        let text_input : String = args[key].convert()?;
+        if text_input == "NULL" {
+            Ok(())
+        }
+        else {
+            Err(Error::UnexpectedPowerArgument {
+                granted,
+                argument : text_input
+            })
+        }
+    };
+
+    player_state.build_government(
+        president,
+        chancellor,
+        president_claimed_blues,
+        chancellor_claimed_blues,
+        enacted,
+        |granted_power, deck_context| {
+            let (takes_first, takes_second) = match granted_power {
+                NoAction => (false, false),
+                Kill(_) | TopDeckPeek(_) | SpecialElection(_) => (true, false),
+                Investigation(_, _) | RevealParty(_, _) | PeekAndBurn(_, _, _) => (true, true)
             };
+            if !takes_first {
+                ensure_unused("first_argument", granted_power)?;
+            }
+            if !takes_second {
+                ensure_unused("second_argument", granted_power)?;
+            }
+            Ok(match granted_power {
+                NoAction => NoAction,
+                Kill(_) => retrieve_player_opt_first().map(Kill)?,
+                Investigation(_, _) => {
+                    Investigation(retrieve_player_opt_first()?, retrieve_policy_opt_second()?)
+                },
+                RevealParty(_, _) => {
+                    RevealParty(retrieve_player_opt_first()?, retrieve_policy_opt_second()?)
+                },
+                TopDeckPeek(_) => TopDeckPeek(retrieve_policy_opt_first(3)?.try_into().unwrap()),
+                SpecialElection(_) => retrieve_player_opt_first().map(SpecialElection)?,
+                PeekAndBurn(_, _, _) => PeekAndBurn(
+                    *retrieve_policy_opt_first(1)?.first().unwrap(),
+                    retrieve_boolean_opt_second()?,
+                    deck_context.atomic_draw(3, 2)
+                )
+            })
+        }
+    )
+}
+
+#[debug_invariant(context.invariant())]
+pub(crate) fn add_government(
+    args : HashMap<String, Value>,
+    context : &mut Context
+) -> Result<Option<String>> {
+    let player_state = &mut context.player_state;
+
+    let government = match government_from_args(&args, player_state)? {
+        Some(government) => government,
+        None => return Ok(Some("gg, fascists won.".to_string()))
+    };
+    let government_text = government.format(&player_state.player_info);
+
+    context.history.record(player_state, "add a government");
+    player_state
+        .governments
+        .push(ElectionResult::Election(government))(player_state, true)?;
+
+    Ok(Some(format!(
+        "Successfully added a government with the following events: {government_text}"
+    )))
+}
+
+#[debug_invariant(context.invariant())]
+pub(crate) fn pop_government(
+    _args : HashMap<String, Value>,
+    context : &mut Context
+) -> Result<Option<String>> {
+    if !context.player_state.governments.is_empty() {
+        context
+            .history
+            .record(&context.player_state, "remove the latest government");
+    }
+
+    let govs = &mut context.player_state.governments;
+    let last = govs.last().cloned();
+
+    let callback = govs.remove(govs.len() - 1);
+    if last.is_some() {
+        let removed_index = context.player_state.governments.len();
+        context
+            .player_state
+            .shift_disabled_information(removed_index, false);
+    }
+
+    if let Some(removed) = last {
+        if let Some(callback) = callback {
+            callback(&context.player_state, true)?;
+            match removed {
+                TopDeck(p, _) => Ok(Some(format!(
+                    "Successfully removed the topdeck failed election which resulted in a {p} \
+                     draw."
+                ))),
+                Election(gov) => Ok(Some(format!(
+                    "Successfully removed the last government with the following events: {}",
+                    gov.format(&context.player_state.player_info)
+                )))
+            }
+        }
+        else {
+            unreachable!()
+        }
+    }
+    else {
+        Ok(Some(
+            "Successfully removed no government because none existed.".to_string()
+        ))
+    }
+}
+
+#[debug_invariant(context.invariant())]
+pub(crate) fn import_log(
+    args : HashMap<String, Value>,
+    context : &mut Context
+) -> Result<Option<String>> {
+    let source : String = args["source"].convert()?;
+
+    let text = if source.to_lowercase() == "clipboard" {
+        Clipboard::new()?.get_text()?
+    }
+    else {
+        fs::read_to_string(&source)?
+    };
+
+    let (imported_state, summary) = log_import::import_log(&text, &context.player_state)
+        .map_err(|errors| Error::LogImportFailed(log_import::format_import_errors(&errors)))?;
+
+    context
+        .history
+        .record(&context.player_state, format!("import {source}"));
+    context.player_state = imported_state;
+    context.player_state.governments.callback()(&context.player_state, true)?;
+
+    Ok(Some(format!(
+        "Successfully imported {} governments, {} top decks and {} facts from {source}.",
+        summary.governments, summary.top_decks, summary.facts
+    )))
+}
+
+fn parse_government_index(
+    args : &HashMap<String, Value>,
+    player_state : &PlayerState,
+    allow_append : bool
+) -> Result<usize> {
+    let index : usize = args["index"].convert()?;
+    let upper_bound = player_state.governments.len() + usize::from(allow_append);
+    if index == 0 || index > upper_bound {
+        Err(Error::BadGovernmentIndex(index))
+    }
+    else {
+        Ok(index)
+    }
+}
+
+fn format_invalidated(invalidated : &[(usize, Error)]) -> String {
+    if invalidated.is_empty() {
+        "All later entries remain valid.".to_string()
+    }
+    else {
+        format!(
+            "The following later entries became invalid and should be edited:\n{}",
+            invalidated
+                .iter()
+                .map(|(index, error)| format!("#{index}: {error}"))
+                .join("\n")
+        )
+    }
+}
+
+/// Splits the history before the (1-based) `index` and lets `modify` change
+/// the staged earlier part and the `later` entries before re-applying them,
+/// re-deriving everything that depends on the earlier history.
+fn rewrite_history(
+    context : &mut Context,
+    index : usize,
+    modify : impl FnOnce(&mut PlayerState, &mut Vec<ElectionResult>) -> Result<()>,
+    action : String
+) -> Result<Vec<(usize, Error)>> {
+    let mut staged = context.player_state.clone();
+    let mut later = staged.governments.split_off(index - 1);
+
+    modify(&mut staged, &mut later)?;
+    let invalidated = staged.replay_governments(later);
+
+    context.history.record(&context.player_state, action);
+    context.player_state.restore(staged);
+    context.player_state.governments.callback()(&context.player_state, true)?;
+
+    Ok(invalidated)
+}
+
+#[debug_invariant(context.invariant())]
+pub(crate) fn edit_government(
+    args : HashMap<String, Value>,
+    context : &mut Context
+) -> Result<Option<String>> {
+    let index = parse_government_index(&args, &context.player_state, false)?;
+    let mut government_text = String::new();
+
+    let invalidated = rewrite_history(
+        context,
+        index,
+        |staged, later| {
+            later.remove(0);
+            let government =
+                government_from_args(&args, staged)?.ok_or(Error::GameAlreadyDecided)?;
+            government_text = government
+                .format(&staged.player_info)
+                .trim_end()
+                .to_string();
+            let _ = staged.governments.push(Election(government));
+            Ok(())
+        },
+        format!("edit government #{index}")
+    )?;
+
+    Ok(Some(format!(
+        "Successfully replaced government #{index} by the following events: {government_text} {}",
+        format_invalidated(&invalidated)
+    )))
+}
+
+#[debug_invariant(context.invariant())]
+pub(crate) fn insert_government(
+    args : HashMap<String, Value>,
+    context : &mut Context
+) -> Result<Option<String>> {
+    let index = parse_government_index(&args, &context.player_state, true)?;
+    let mut government_text = String::new();
+
+    let invalidated = rewrite_history(
+        context,
+        index,
+        |staged, _later| {
+            let government =
+                government_from_args(&args, staged)?.ok_or(Error::GameAlreadyDecided)?;
+            government_text = government
+                .format(&staged.player_info)
+                .trim_end()
+                .to_string();
+            let _ = staged.governments.push(Election(government));
+            staged.shift_deck_count_assertions(index - 1, true);
+            staged.shift_disabled_information(index - 1, true);
+            Ok(())
+        },
+        format!("insert government #{index}")
+    )?;
+
+    Ok(Some(format!(
+        "Successfully inserted government #{index} with the following events: {government_text} {}",
+        format_invalidated(&invalidated)
+    )))
+}
+
+#[debug_invariant(context.invariant())]
+pub(crate) fn delete_government(
+    args : HashMap<String, Value>,
+    context : &mut Context
+) -> Result<Option<String>> {
+    let index = parse_government_index(&args, &context.player_state, false)?;
+    let removed_text = context.player_state.governments[index - 1]
+        .format(&context.player_state.player_info)
+        .trim_end()
+        .to_string();
+
+    let invalidated = rewrite_history(
+        context,
+        index,
+        |staged, later| {
+            later.remove(0);
+            staged.shift_deck_count_assertions(index, false);
+            staged.shift_disabled_information(index - 1, false);
+            Ok(())
+        },
+        format!("delete government #{index}")
+    )?;
+
+    Ok(Some(format!(
+        "Successfully deleted government #{index} with the following events: {removed_text} {}",
+        format_invalidated(&invalidated)
+    )))
+}
+
+#[debug_invariant(context.invariant())]
+pub(crate) fn deck_count(
+    args : HashMap<String, Value>,
+    context : &mut Context
+) -> Result<Option<String>> {
+    let observed : usize = args["observed_cards"].convert()?;
+    let correct : bool = args["correct"].convert()?;
+    let government : String = args["before_government"].convert()?;
+
+    let index = if government.to_lowercase() == "next" {
+        context.player_state.governments.len() + 1
+    }
+    else {
+        let index : usize = government
+            .parse()
+            .map_err(|_| Error::ParseGovernmentIndex(government.clone()))?;
+        if index == 0 || index > context.player_state.governments.len() + 1 {
+            return Err(Error::BadGovernmentIndex(index));
+        }
+        index
+    };
+
+    let mut staged = context.player_state.clone();
+    let _ = staged.governments.split_off(index - 1);
+    staged.deck_count_assertions.remove(&(index - 1));
+    let tracked = staged.build_next_card_context()?;
+    staged.correct_card_context(tracked, observed)?;
+
+    let comparison = if tracked.cards_left == observed {
+        format!(
+            "The tracked deck holds {} cards before government #{index}, matching the observation.",
+            tracked.cards_left
+        )
+    }
+    else {
+        format!(
+            "The tracked deck holds {} cards (discard pile: {} cards) before government #{index}, \
+             but {observed} cards were observed.",
+            tracked.cards_left, tracked.cards_discarded
+        )
+    };
 
-            let dot_process = command
-                .stdin(Stdio::null())
-                .stderr(Stdio::piped())
-                .stdout(Stdio::piped())
-                .output()?;
+    if !correct {
+        return Ok(Some(comparison));
+    }
 
-            if !dot_process.stdout.is_empty() {
-                return Err(Error::UnexpectedStdout(dot_process.stdout));
-            }
-            if !dot_process.stderr.is_empty() {
-                return Err(Error::UnexpectedStderr(dot_process.stderr));
-            }
+    let invalidated = rewrite_history(
+        context,
+        index,
+        |staged, _later| {
+            staged.deck_count_assertions.insert(index - 1, observed);
+            Ok(())
+        },
+        format!("assert {observed} cards in the deck before government #{index}")
+    )?;
 
-            let image = image::io::Reader::open(&imagefile)?.decode()?;
-            let image = image.as_rgba8().ok_or(Error::EncodingFailed)?;
-            let mut clipboard = Clipboard::new()?;
-            clipboard.set_image(ImageData {
-                width : image.width() as usize,
-                height : image.height() as usize,
-                bytes : std::borrow::Cow::Borrowed(image.as_bytes())
-            })?;
+    let mut staged = context.player_state.clone();
+    let _ = staged.governments.split_off(index - 1);
+    let corrected = staged.build_next_card_context()?;
 
-            fs::remove_file(&dotfile)?;
+    Ok(Some(format!(
+        "{comparison} Corrected the tracked deck to {} cards with {} cards in the discard pile{}. \
+         {}",
+        corrected.cards_left,
+        corrected.cards_discarded,
+        if corrected.shuffle_index != tracked.shuffle_index {
+            ", which changes when the deck got reshuffled"
         }
+        else {
+            ""
+        },
+        format_invalidated(&invalidated)
+    )))
+}
 
-        Ok(())
-    });
+#[debug_invariant(context.invariant())]
+pub(crate) fn undo(
+    _args : HashMap<String, Value>,
+    context : &mut Context
+) -> Result<Option<String>> {
+    match context.history.undo(&mut context.player_state) {
+        Some(action) => {
+            context.player_state.governments.callback()(&context.player_state, true)?;
+            Ok(Some(format!("Successfully undid \"{action}\".")))
+        },
+        None => Ok(Some("There is nothing left to undo.".to_string()))
+    }
+}
+
+#[debug_invariant(context.invariant())]
+pub(crate) fn redo(
+    _args : HashMap<String, Value>,
+    context : &mut Context
+) -> Result<Option<String>> {
+    match context.history.redo(&mut context.player_state) {
+        Some(action) => {
+            context.player_state.governments.callback()(&context.player_state, true)?;
+            Ok(Some(format!("Successfully redid \"{action}\".")))
+        },
+        None => Ok(Some("There is nothing left to redo.".to_string()))
+    }
+}
+
+#[debug_invariant(context.invariant())]
+pub(crate) fn branch(
+    args : HashMap<String, Value>,
+    context : &mut Context
+) -> Result<Option<String>> {
+    let name : String = args["name"].convert()?;
+    let previous = context.branches.active().to_owned();
 
     context
-        .player_state
-        .available_information
-        .register_callback(CallbackKind::GovernmentOverviewGraph, Rc::clone(&closure));
-    context
-        .player_state
-        .governments
-        .register_callback(CallbackKind::GovernmentOverviewGraph, Rc::clone(&closure));
-    context.player_state.governments.callback()(&context.player_state, false)?;
+        .branches
+        .fork(&name, &context.player_state, &context.history)?;
 
     Ok(Some(format!(
-        "Run \"dot -Tpng -o {resp_filename}.png {resp_filename}.dot\" in a separate shell (e.g. \
-         bash, cmd, powershell, ...) in the current working directory to generate the graph."
+        "Created the branch \"{name}\" from \"{previous}\" and switched to it."
     )))
 }
 
 #[debug_invariant(context.invariant())]
-pub(crate) fn name(
+pub(crate) fn switch(
     args : HashMap<String, Value>,
     context : &mut Context
 ) -> Result<Option<String>> {
-    let position : usize = args["position"].convert()?;
-    let name : String = args["display_name"].convert()?;
+    let name : String = args["name"].convert()?;
 
     context
-        .player_state
-        .player_info
-        .get_mut(&position)
-        .ok_or(Error::BadPlayerID(position))?
-        .name = name.clone();
+        .branches
+        .switch(&name, &mut context.player_state, &mut context.history)?;
+    context.player_state.governments.callback()(&context.player_state, true)?;
 
-    Ok(Some(format!(
-        "Successfully registered the name {name} for player {position}."
-    )))
+    Ok(Some(format!("Switched to the branch \"{name}\".")))
 }
 
 #[debug_invariant(context.invariant())]
-pub(crate) fn add_government(
-    args : HashMap<String, Value>,
+pub(crate) fn branches(
+    _args : HashMap<String, Value>,
     context : &mut Context
 ) -> Result<Option<String>> {
-    let player_state = &mut context.player_state;
-    let president : String = args["president"].convert()?;
-    let president = parse_player_name(&president, &player_state.player_info)?;
-    let chancellor : String = args["chancellor"].convert()?;
-    let chancellor = parse_player_name(&chancellor, &player_state.player_info)?;
-    let presidential_pattern : String = args["presidential_blues"].convert()?;
-    let chancellor_pattern : String = args["chancellor_blues"].convert()?;
+    let branches = &context.branches;
 
-    player_state.player_interactable(president, &player_state.player_info)?;
-    player_state.player_interactable(chancellor, &player_state.player_info)?;
+    Ok(Some(
+        branches
+            .names()
+            .into_iter()
+            .map(|(name, active)| {
+                let player_state = branches.get(name, &context.player_state)?;
+                Ok(format!(
+                    "{} {name}: {} governments, {} facts",
+                    if active { "*" } else { " " },
+                    player_state.governments.len(),
+                    player_state.available_information.len()
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?
+            .join("\n")
+    ))
+}
 
-    if !player_state.is_eligible_president(president) {
-        return Err(Error::NotEligiblePresident(
-            president,
-            context.player_state.player_info.clone()
-        ));
-    }
+#[debug_invariant(context.invariant())]
+pub(crate) fn compare(
+    args : HashMap<String, Value>,
+    context : &mut Context
+) -> Result<Option<String>> {
+    let left_name : String = args["left"].convert()?;
+    let right_name : String = args["right"].convert()?;
+    let filter_args = parse_filter_args(args)?;
+    let left = context.branches.get(&left_name, &context.player_state)?;
+    let right = context.branches.get(&right_name, &context.player_state)?;
+
+    let left_histogram = filtered_histogramm(filter_args, left, &[])?;
+    let right_histogram = filtered_histogramm(filter_args, right, &[])?;
+    let chance = |histogram : &advice::RoleHistogram, pid : &PlayerID, role : SecretRole| {
+        histogram
+            .get(pid)
+            .map(|(roles, total)| {
+                roles
+                    .get(&role)
+                    .copied()
+                    .unwrap_or(FilterResult::none(*total))
+            })
+            .unwrap_or(FilterResult::none(0))
+    };
 
-    if !player_state.is_eligible_chancellor(chancellor) || chancellor == president {
-        return Err(Error::NotEligibleChancellor(
-            chancellor,
-            context.player_state.player_info.clone()
-        ));
+    // the branches may have been set up from different perspectives
+    let notes = if left.perspective == right.perspective {
+        perspective_note(left)
     }
+    else {
+        let viewer = |branch : &PlayerState| {
+            branch.perspective.as_ref().map_or_else(
+                || "a spectator".to_owned(),
+                |perspective| perspective.format(&branch.player_info)
+            )
+        };
+        format!(
+            "\"{left_name}\" is seen by {}, \"{right_name}\" by {}.\n",
+            viewer(left),
+            viewer(right)
+        )
+    };
 
-    let president_claimed_blues = parse_pattern(presidential_pattern, 3, 3)?.0;
-    let chancellor_claimed_blues = parse_pattern(chancellor_pattern, 2, 2)?.0;
+    Ok(Some(
+        notes
+            + &std::iter::once(format!(
+                "Chance of being a liberal and of being Hitler in \"{left_name}\" vs \
+                 \"{right_name}\":"
+            ))
+            .chain(left.player_info.keys().map(|pid| {
+                format!(
+                    "Player {}: liberal {} vs {}, Hitler {} vs {}",
+                    left.player_info.format_name(*pid),
+                    chance(&left_histogram, pid, SecretRole::Liberal),
+                    chance(&right_histogram, pid, SecretRole::Liberal),
+                    chance(&left_histogram, pid, SecretRole::Hitler),
+                    chance(&right_histogram, pid, SecretRole::Hitler)
+                )
+            }))
+            .join("\n")
+    ))
+}
 
-    let immediate_conflict = president_claimed_blues > 0 && chancellor_claimed_blues == 0;
+#[debug_invariant(context.invariant())]
+pub(crate) fn topdeck(
+    args : HashMap<String, Value>,
+    context : &mut Context
+) -> Result<Option<String>> {
+    let drawn_policy : String = args["drawn_policy"].convert()?;
+    let drawn_policy : Policy = drawn_policy.parse()?;
 
-    let retrieve_player_opt_first = || -> Result<_> {
-        let text_input : String = args["first_argument"].convert()?;
-        let extraced_player = parse_player_name(&text_input, &player_state.player_info)?;
-        player_state.player_interactable(extraced_player, &player_state.player_info)?;
-        Ok(extraced_player)
-    };
+    context
+        .history
+        .record(&context.player_state, "add a top deck");
+    context.player_state.governments.push(TopDeck(
+        drawn_policy,
+        context.player_state.build_next_card_context()?
+    ))(&context.player_state, true)?;
 
-    let retrieve_policy_opt_second = || -> Result<_> {
-        let text_input : String = args["second_argument"].convert()?;
-        Ok(*parse_pattern(text_input, 1, 1)?.2.first().unwrap())
-    };
+    Ok(Some(format!(
+        "Successfully added a top-deck that resulted in a {drawn_policy} policy enactment."
+    )))
+}
 
-    let retrieve_policy_opt_first = |count| -> Result<_> {
-        let text_input : String = args["first_argument"].convert()?;
-        Ok(parse_pattern(text_input, count, count)?.2)
+#[debug_invariant(context.invariant())]
+pub(crate) fn deck_timeline(
+    _args : HashMap<String, Value>,
+    context : &mut Context
+) -> Result<Option<String>> {
+    let player_state = &context.player_state;
+    let mut previous_shuffle = None;
+    let mut lines = vec![];
+    let observed_marker = |index| {
+        if player_state.deck_count_assertions.contains_key(&index) {
+            " (observed)"
+        }
+        else {
+            ""
+        }
     };
 
-    let retrieve_boolean_opt_second = || -> Result<_> {
-        let text_input : bool = args["first_argument"].convert()?;
-        Ok(text_input)
-    };
+    for (index, er) in player_state.governments.iter().enumerate() {
+        let card_context = er.card_context();
+        if previous_shuffle != Some(card_context.shuffle_index) {
+            lines.push(format!(
+                "Shuffle #{}{}:",
+                card_context.shuffle_index + 1,
+                if previous_shuffle.is_some() {
+                    " (reshuffled the discard pile into the deck)"
+                }
+                else {
+                    ""
+                }
+            ));
+            previous_shuffle = Some(card_context.shuffle_index);
+        }
+
+        let (drawn, _discarded) = er.cards_total_drawn_discarded();
+        let drawn_by = match er {
+            TopDeck(_, _) => "the top deck".to_string(),
+            Election(gov) => format!(
+                "president {} and chancellor {}",
+                player_state.player_info.format_name(gov.president),
+                player_state.player_info.format_name(gov.chancellor)
+            )
+        };
+        lines.push(format!(
+            "{}. Deck: {} cards{}, discard pile: {} cards. {drawn} cards consumed by {drawn_by} \
+             (claimed {}).",
+            index + 1,
+            card_context.cards_left,
+            observed_marker(index),
+            card_context.cards_discarded,
+            format_claim(er.seen_blues(), drawn)
+        ));
+    }
 
-    let policy_passed =
-        if (immediate_conflict && president_claimed_blues > 0) || president_claimed_blues == 0 {
-            Policy::Fascist
+    let next_context = player_state.build_next_card_context()?;
+    lines.push(format!(
+        "Next government: deck: {} cards{}, discard pile: {} cards{}.",
+        next_context.cards_left,
+        observed_marker(player_state.governments.len()),
+        next_context.cards_discarded,
+        if previous_shuffle.is_some() && previous_shuffle != Some(next_context.shuffle_index) {
+            " after a reshuffle"
         }
         else {
-            Policy::Liberal
-        };
+            ""
+        }
+    ));
 
-    let prev_fas_policies = player_state.count_policies_on_board(Policy::Fascist);
+    Ok(Some(lines.into_iter().join("\n")))
+}
 
-    let deck_context = player_state.build_next_card_context();
+#[debug_invariant(context.invariant())]
+pub(crate) fn liars(
+    _args : HashMap<String, Value>,
+    context : &mut Context
+) -> Result<Option<String>> {
+    let player_state = &context.player_state;
+    let format_group = |players : &BTreeSet<PlayerID>| {
+        players
+            .iter()
+            .map(|pid| player_state.player_info.format_name(*pid))
+            .join(" and ")
+    };
 
-    let presidential_action = if policy_passed == Policy::Fascist {
-        if prev_fas_policies >= 5 {
-            return Ok(Some("gg, fascists won.".to_string()));
-        }
+    Ok(Some(
+        player_state
+            .shuffle_election_results()
+            .iter()
+            .map(|shuffle| {
+                let shuffle_number = shuffle.shuffle_index + 1;
+                match liars::shuffle_liar_sets(player_state, shuffle) {
+                    None => format!(
+                        "Shuffle #{shuffle_number}: the drawn cards can't be explained by any \
+                         deck, check for missed governments or the deck_count command."
+                    ),
+                    Some(liar_sets) if liar_sets.iter().any(|liars| liars.is_empty()) => {
+                        format!("Shuffle #{shuffle_number}: everybody may have told the truth.")
+                    },
+                    Some(liar_sets) => format!(
+                        "Shuffle #{shuffle_number}: one of the following groups must have lied: \
+                         {}. Hence at least one of each of these groups is a fascist: {}.",
+                        liar_sets
+                            .iter()
+                            .map(|liars| format!("({})", format_group(liars)))
+                            .join(", "),
+                        liars::minimal_transversals(&liar_sets)
+                            .iter()
+                            .map(|suspicious| format!("({})", format_group(suspicious)))
+                            .join(", ")
+                    )
+                }
+            })
+            .join("\n")
+    ))
+}
 
-        match player_state.table_configuration.fascist_board_configuration[prev_fas_policies] {
-            NoAction => NoAction,
-            Kill(_) => retrieve_player_opt_first().map(Kill)?,
-            Investigation(_, _) => {
-                Investigation(retrieve_player_opt_first()?, retrieve_policy_opt_second()?)
-            },
-            RevealParty(_, _) => {
-                RevealParty(retrieve_player_opt_first()?, retrieve_policy_opt_second()?)
-            },
-            TopDeckPeek(_) => TopDeckPeek(retrieve_policy_opt_first(3)?.try_into().unwrap()),
-            SpecialElection(_) => retrieve_player_opt_first().map(SpecialElection)?,
-            PeekAndBurn(_, _, _) => PeekAndBurn(
-                *retrieve_policy_opt_first(1)?.first().unwrap(),
-                retrieve_boolean_opt_second()?,
-                deck_context.atomic_draw(3, 2)
-            )
-        }
+fn describe_next_draw(next_draw : &forecast::NextDraw, truthful : bool) -> String {
+    let assumption = if truthful {
+        "assuming all claims since the shuffle are true"
     }
     else {
-        NoAction
+        "only taking passed policies into account"
     };
+    if next_draw.fresh {
+        format!(
+            "The next government draws from a freshly shuffled deck of {} liberal and {} fascist \
+             policies.",
+            next_draw.shuffled_liberal, next_draw.shuffled_fascist
+        )
+    }
+    else {
+        format!(
+            "The next government draws from a deck shuffled with {} liberal and {} fascist \
+             policies, {assumption}.",
+            next_draw.shuffled_liberal, next_draw.shuffled_fascist
+        )
+    }
+}
 
-    let government = ElectedGovernment {
-        president,
-        chancellor,
-        president_claimed_blues,
-        chancellor_claimed_blues,
-        conflict : immediate_conflict,
-        policy_passed,
-        presidential_action,
-        deck_context,
-        chancellor_confirmed_not_hitler : prev_fas_policies
-            >= player_state
-                .table_configuration
-                .hitler_zone_passed_fascist_policies
+#[debug_invariant(context.invariant())]
+pub(crate) fn next_gov(
+    args : HashMap<String, Value>,
+    context : &mut Context
+) -> Result<Option<String>> {
+    let player_state = &context.player_state;
+    let pattern : String = args["pattern"].convert()?;
+    let truthful : bool = args["truthful"].convert()?;
+
+    let (num_lib_in_pattern, pattern_length, pattern) = parse_pattern(pattern, usize::MAX, 1)?;
+    let next_draw = forecast::next_draw(player_state, pattern_length, truthful)?;
+    let analysis = FilterResult {
+        num_matching : next_draw
+            .histogram
+            .get(&num_lib_in_pattern)
+            .copied()
+            .unwrap_or(0),
+        num_checked : next_draw.histogram.values().sum()
     };
-    let government_text = government.format(&player_state.player_info);
-
-    player_state
-        .governments
-        .push(ElectionResult::Election(government))(player_state, true)?;
 
     Ok(Some(format!(
-        "Successfully added a government with the following events: {government_text}"
+        "{}\nThere is a {analysis} chance for the claim pattern {} to match the next {} cards.",
+        describe_next_draw(&next_draw, truthful),
+        pattern.iter().map(|p| p.to_string()).join(""),
+        pattern_length
     )))
 }
 
 #[debug_invariant(context.invariant())]
-pub(crate) fn pop_government(
-    _args : HashMap<String, Value>,
+pub(crate) fn dist_gov(
+    args : HashMap<String, Value>,
     context : &mut Context
 ) -> Result<Option<String>> {
-    let govs = &mut context.player_state.governments;
-    let last = govs.last().cloned();
+    let player_state = &context.player_state;
+    let window_size : usize = args["window_size"].convert()?;
+    let truthful : bool = args["truthful"].convert()?;
 
-    let callback = govs.remove(govs.len() - 1);
+    let next_draw = forecast::next_draw(player_state, window_size, truthful)?;
+    let deck_count : usize = next_draw.histogram.values().sum();
 
-    if let Some(removed) = last {
-        if let Some(callback) = callback {
-            callback(&context.player_state, true)?;
-            match removed {
-                TopDeck(p, _) => Ok(Some(format!(
-                    "Successfully removed the topdeck failed election which resulted in a {p} \
-                     draw."
-                ))),
-                Election(gov) => Ok(Some(format!(
-                    "Successfully removed the last government with the following events: {}",
-                    gov.format(&context.player_state.player_info)
-                )))
-            }
-        }
-        else {
-            unreachable!()
-        }
-    }
-    else {
-        Ok(Some(
-            "Successfully removed no government because none existed.".to_string()
-        ))
-    }
+    Ok(Some(
+        std::iter::once(describe_next_draw(&next_draw, truthful))
+            .chain(next_draw.histogram.iter().map(|(blues, count)| {
+                format!(
+                    "{}{}: {}",
+                    Policy::Fascist.to_string().repeat(window_size - blues),
+                    Policy::Liberal.to_string().repeat(*blues),
+                    FilterResult {
+                        num_matching : *count,
+                        num_checked : deck_count
+                    }
+                )
+            }))
+            .join("\n")
+    ))
 }
 
 #[debug_invariant(context.invariant())]
-pub(crate) fn topdeck(
+pub(crate) fn piles(
     args : HashMap<String, Value>,
     context : &mut Context
 ) -> Result<Option<String>> {
-    let drawn_policy : String = args["drawn_policy"].convert()?;
-    let drawn_policy : Policy = drawn_policy.parse()?;
-
-    context.player_state.governments.push(TopDeck(
-        drawn_policy,
-        context.player_state.build_next_card_context()
-    ))(&context.player_state, true)?;
+    let piles = forecast::current_piles(parse_filter_args(args)?, &context.player_state)?;
+    let total : usize = piles.draw_pile_liberals.values().sum();
+    let expected = piles
+        .draw_pile_liberals
+        .iter()
+        .map(|(liberals, weight)| (liberals * weight) as f64)
+        .sum::<f64>()
+        / total as f64;
 
-    Ok(Some(format!(
-        "Successfully added a top-deck that resulted in a {drawn_policy} policy enactment."
-    )))
+    Ok(Some(
+        perspective_note(&context.player_state)
+            + &[
+                format!(
+                    "Draw pile: {} cards, discard pile: {} cards, {} liberal policies off the \
+                     board.",
+                    piles.draw_pile_cards, piles.discard_pile_cards, piles.liberals_off_board
+                ),
+                format!("Expected liberal policies in the draw pile: {expected:.2}")
+            ]
+            .into_iter()
+            .chain(piles.draw_pile_liberals.iter().map(|(liberals, weight)| {
+                format!(
+                    "{liberals} liberal policies in the draw pile, {} in the discard pile: {}",
+                    piles.liberals_off_board - liberals,
+                    FilterResult {
+                        num_matching : *weight,
+                        num_checked : total
+                    }
+                )
+            }))
+            .join("\n")
+    ))
 }
 
 #[debug_invariant(context.invariant())]
@@ -1496,12 +3558,18 @@ pub(crate) fn total_draw_probability(
             .shuffle_election_results()
             .iter()
             .map(|sa| {
+                let Some(seen_blues) = sa.total_seen_blues()
+                else {
+                    return format!(
+                        "The shuffle #{} contains unknown claims.",
+                        sa.shuffle_index + 1
+                    );
+                };
                 let analysis = next_blues_count(
                     sa.initial_deck_liberal,
                     sa.initial_deck_fascist,
                     sa.total_leftover,
-                    sa.initial_deck_liberal
-                        .saturating_sub(sa.total_seen_blues()),
+                    sa.initial_deck_liberal.saturating_sub(seen_blues),
                     0,
                     0
                 );
@@ -1587,8 +3655,9 @@ pub(crate) fn probability_tree(
     context.player_state.governments.callback()(&context.player_state, false)?;
 
     Ok(Some(format!(
-        "Run \"dot -Tpng -o {resp_filename}.png {resp_filename}.dot\" in a separate shell (e.g. \
-         bash, cmd, powershell, ...) in the current working directory to generate the graph."
+        "{}Run \"dot -Tpng -o {resp_filename}.png {resp_filename}.dot\" in a separate shell (e.g. \
+         bash, cmd, powershell, ...) in the current working directory to generate the graph.",
+        perspective_note(&context.player_state)
     )))
 }
 
@@ -1617,6 +3686,9 @@ pub(crate) fn create_game_config(
         serde_json::to_string_pretty(&config)?
     )?;
 
+    context
+        .history
+        .record(&context.player_state, "create a game configuration");
     context.player_state = PlayerState::new(config);
 
     Ok(Some(format!(
@@ -1634,7 +3706,11 @@ pub(crate) fn load_game_config(
     let mut player_state = &mut context.player_state;
     let filename : String = args["filename"].convert()?;
 
-    *player_state = PlayerState::new(serde_json::from_slice(&fs::read(&filename)?)?);
+    let configuration = serde_json::from_slice(&fs::read(&filename)?)?;
+    context
+        .history
+        .record(player_state, format!("load {filename}"));
+    *player_state = PlayerState::new(configuration);
 
     Ok(Some(format!(
         "Successfully loaded the {filename} configuration file. This resulted in a game with the \
@@ -0,0 +1,145 @@
+use std::collections::BTreeMap;
+
+use itertools::Itertools;
+
+use crate::{
+    error::{Error, Result},
+    expression::Claim,
+    secret_role::SecretRole,
+    PlayerID
+};
+
+use super::{
+    game_configuration::GameConfiguration, parse_player_name, PlayerFormatable, PlayerInfos,
+    PlayerManager
+};
+
+/// What the user knows as one of the players: their own role and, as a
+/// fascist, (some of) their teammates.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Perspective {
+    pub seat : PlayerID,
+    pub role : SecretRole,
+    pub teammates : Vec<(PlayerID, Claim)>
+}
+
+impl Perspective {
+    /// Parses the comma-separated teammates, each optionally followed by
+    /// `:h` to mark Hitler, e.g. `5,7:h`. They have to be exactly the
+    /// teammates the role gets to know at this table.
+    pub(super) fn new(
+        seat : PlayerID,
+        role : SecretRole,
+        teammates : &str,
+        player_info : &PlayerInfos,
+        configuration : &GameConfiguration
+    ) -> Result<Self> {
+        let teammates = teammates
+            .split(|c : char| c == ',' || c.is_whitespace())
+            .filter(|teammate| !teammate.is_empty())
+            .map(|teammate| {
+                let (name, claim) = match teammate.split_once(':') {
+                    Some((name, claim)) => (name, claim.parse::<Claim>()?),
+                    None => (teammate, Claim::Fascist)
+                };
+                if claim == Claim::Liberal {
+                    return Err(Error::BadPerspective(
+                        "teammates have to be fascists or Hitler"
+                    ));
+                }
+                let player = parse_player_name(name, player_info)?;
+                player_info.player_exists(player)?;
+                Ok((player, claim))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        if teammates.iter().any(|(player, _)| *player == seat) {
+            return Err(Error::BadPerspective(
+                "the own seat can't be listed as a teammate"
+            ));
+        }
+        if !teammates.iter().map(|(player, _)| player).all_unique() {
+            return Err(Error::BadPerspective(
+                "every teammate can only be listed once"
+            ));
+        }
+
+        let listed_hitlers = teammates
+            .iter()
+            .filter(|(_, claim)| *claim == Claim::Hitler)
+            .count();
+        let listed_fascists = teammates.len() - listed_hitlers;
+        let num_regular_fascists = configuration.num_regular_fascists;
+        match role {
+            SecretRole::Liberal if !teammates.is_empty() => {
+                return Err(Error::BadPerspective(
+                    "a liberal doesn't know any teammates"
+                ));
+            },
+            SecretRole::RegularFascist
+                if listed_hitlers != 1 || listed_fascists + 1 != num_regular_fascists =>
+            {
+                return Err(Error::BadPerspective(
+                    "a fascist knows the whole team, list all other fascists and Hitler"
+                ));
+            },
+            SecretRole::Hitler if listed_hitlers > 0 => {
+                return Err(Error::BadPerspective("there is only one Hitler"));
+            },
+            SecretRole::Hitler
+                if !configuration.hitler_knows_fascists() && !teammates.is_empty() =>
+            {
+                return Err(Error::BadPerspective(
+                    "Hitler only knows the fascists in games of up to 6 players"
+                ));
+            },
+            SecretRole::Hitler
+                if configuration.hitler_knows_fascists()
+                    && listed_fascists != num_regular_fascists =>
+            {
+                return Err(Error::BadPerspective(
+                    "Hitler knows the fascists in games of up to 6 players, list all of them"
+                ));
+            },
+            _ => {}
+        }
+
+        Ok(Self {
+            seat,
+            role,
+            teammates
+        })
+    }
+
+    pub(super) fn allows(&self, roles : &BTreeMap<PlayerID, SecretRole>) -> bool {
+        roles.get(&self.seat) == Some(&self.role)
+            && self
+                .teammates
+                .iter()
+                .all(|(player, claim)| roles.get(player).is_some_and(|role| claim.holds(*role)))
+    }
+}
+
+impl PlayerFormatable for Perspective {
+    fn format(&self, player_info : &PlayerInfos) -> String {
+        let teammates = self
+            .teammates
+            .iter()
+            .map(|(player, claim)| match claim {
+                Claim::Hitler => format!("Player {} (Hitler)", player_info.format_name(*player)),
+                _ => format!("Player {}", player_info.format_name(*player))
+            })
+            .join(", ");
+        format!(
+            "player {} as {}{}",
+            player_info.format_name(self.seat),
+            self.role,
+            if teammates.is_empty() {
+                String::new()
+            }
+            else {
+                format!(" knowing {teammates}")
+            }
+        )
+    }
+}
@@ -0,0 +1,179 @@
+use crate::{
+    deck::FilterResult,
+    error::{Error, Result},
+    policy::Policy,
+    secret_role::SecretRole,
+    PlayerID
+};
+
+use super::{
+    advice::{fascist_probability, RoleHistogram},
+    claims_conflict,
+    filter_engine::filtered_histogramm,
+    forecast::next_draw,
+    ElectionResult::Election,
+    PlayerState,
+    PresidentialAction::NoAction
+};
+
+/// How the public sees a fascist before and (in expectation) after a claim.
+pub(super) struct Standing {
+    pub player : PlayerID,
+    pub liberal : (f64, f64),
+    pub hitler : (f64, f64)
+}
+
+pub(super) struct ClaimRating {
+    pub claimed_blues : usize,
+    /// the share of hands the publicly known deck yields with this many blues
+    pub believability : f64,
+    /// the chance that the chancellor contradicts the claim
+    pub conflict : f64,
+    /// `None` if the public would be faced with a contradiction
+    pub standings : Option<Vec<Standing>>
+}
+
+fn role_probability(histogram : &RoleHistogram, player : PlayerID, role : SecretRole) -> f64 {
+    histogram
+        .get(&player)
+        .and_then(|(roles, _total)| roles.get(&role))
+        .map_or(0.0, FilterResult::probability)
+}
+
+/// Rates every claim the fascist president of the perspective could make
+/// about a draw with `drawn_blues` liberal policies when discarding
+/// `discarded` and handing the rest to `chancellor`. A liberal chancellor is
+/// assumed to claim the truth, a fascist one the closest consistent claim.
+pub(super) fn plan_claims(
+    filter_args : (bool, bool),
+    player_state : &PlayerState,
+    chancellor : PlayerID,
+    drawn_blues : usize,
+    discarded : Policy
+) -> Result<(f64, Vec<ClaimRating>)> {
+    let president = player_state
+        .perspective
+        .as_ref()
+        .filter(|perspective| perspective.role.is_fascist())
+        .ok_or(Error::BadPerspective(
+            "claims can only be planned as a fascist set up with whoami"
+        ))?
+        .seat;
+    let passed_blues = match (discarded, drawn_blues) {
+        (Policy::Liberal, 1..=3) => drawn_blues - 1,
+        (Policy::Fascist, 0..=2) => drawn_blues,
+        _ => return Err(Error::DiscardNotDrawn(discarded))
+    };
+
+    let own_view = filtered_histogramm(filter_args, player_state, &[])?;
+    let chancellor_liberal = role_probability(&own_view, chancellor, SecretRole::Liberal);
+    let fascists = own_view
+        .keys()
+        .copied()
+        .filter(|pid| {
+            let fascist = fascist_probability(&own_view, *pid);
+            fascist.num_matching == fascist.num_checked
+        })
+        .collect::<Vec<_>>();
+
+    let mut public = player_state.clone();
+    public.perspective = None;
+    let before = filtered_histogramm(filter_args, &public, &[])?;
+    let deck = next_draw(&public, 3, false)?;
+    let hands : usize = deck.histogram.values().sum();
+
+    let ratings = (0..=3usize)
+        .map(|claimed_blues| {
+            let fascist_claim =
+                passed_blues.clamp(claimed_blues.saturating_sub(1), claimed_blues.min(2));
+            // a liberal chancellor plays a blue if they can, a fascist one a red
+            let responses = [
+                (
+                    chancellor_liberal,
+                    passed_blues,
+                    if passed_blues > 0 {
+                        Policy::Liberal
+                    }
+                    else {
+                        Policy::Fascist
+                    }
+                ),
+                (
+                    1.0 - chancellor_liberal,
+                    fascist_claim,
+                    if passed_blues < 2 {
+                        Policy::Fascist
+                    }
+                    else {
+                        Policy::Liberal
+                    }
+                )
+            ];
+            let conflict = responses
+                .iter()
+                .filter(|(_, chancellor_claim, _)| {
+                    claims_conflict(claimed_blues, *chancellor_claim)
+                })
+                .fold(0.0, |conflict, (weight, _, _)| conflict + weight);
+
+            let mut outcomes = vec![];
+            for (weight, chancellor_claim, enacted) in responses {
+                if weight == 0.0 {
+                    continue;
+                }
+                let government = public
+                    .build_government(
+                        president,
+                        chancellor,
+                        Some(claimed_blues),
+                        Some(chancellor_claim),
+                        Some(enacted),
+                        |_granted, _context| Ok(NoAction)
+                    )?
+                    .ok_or(Error::GameAlreadyDecided)?;
+                let mut hypothetical = public.clone();
+                let _ = hypothetical.governments.push(Election(government));
+                match filtered_histogramm(filter_args, &hypothetical, &[]) {
+                    Ok(histogram) => outcomes.push((weight, histogram)),
+                    Err(Error::LogicalInconsistency) => {},
+                    Err(e) => return Err(e)
+                }
+            }
+
+            let total_weight : f64 = outcomes.iter().map(|(weight, _)| weight).sum();
+            let expected = |player, role| {
+                outcomes
+                    .iter()
+                    .map(|(weight, histogram)| weight * role_probability(histogram, player, role))
+                    .sum::<f64>()
+                    / total_weight
+            };
+            let standings = (!outcomes.is_empty()).then(|| {
+                fascists
+                    .iter()
+                    .map(|player| Standing {
+                        player : *player,
+                        liberal : (
+                            role_probability(&before, *player, SecretRole::Liberal),
+                            expected(*player, SecretRole::Liberal)
+                        ),
+                        hitler : (
+                            role_probability(&before, *player, SecretRole::Hitler),
+                            expected(*player, SecretRole::Hitler)
+                        )
+                    })
+                    .collect()
+            });
+
+            Ok(ClaimRating {
+                claimed_blues,
+                believability : deck.histogram.get(&claimed_blues).copied().unwrap_or(0) as f64
+                    / hands.max(1) as f64,
+                conflict,
+                standings
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok((chancellor_liberal, ratings))
+}
@@ -0,0 +1,412 @@
+use std::{collections::BTreeMap, fmt, str};
+
+use itertools::Itertools;
+
+use crate::{
+    error::{Error, Result},
+    policy::Policy,
+    secret_role::SecretRole,
+    PlayerID
+};
+
+use super::{
+    filter_engine::{filter_assigned_roles, filtered_histogramm},
+    ElectionResult::*,
+    PlayerState,
+    PresidentialAction::*
+};
+
+const LIBERAL_POLICIES_TO_WIN : usize = 5;
+
+/// How the liberals pick their chancellors and kill targets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LiberalStrategy {
+    /// uniformly among everybody eligible
+    Random,
+    /// the most trusted (resp. most suspicious) player according to the
+    /// currently known information
+    Informed
+}
+
+impl str::FromStr for LiberalStrategy {
+    type Err = Error;
+
+    fn from_str(s : &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "random" => Ok(LiberalStrategy::Random),
+            "informed" => Ok(LiberalStrategy::Informed),
+            _ => Err(Error::BadStrategy {
+                found : s.to_owned(),
+                expected : "random or informed"
+            })
+        }
+    }
+}
+
+/// How the fascists play their policies and pick their chancellors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FascistStrategy {
+    /// always play fascist policies and pick fellow fascists
+    Aggressive,
+    /// only play fascist policies when governing with a fellow fascist,
+    /// otherwise act like a liberal
+    Cautious
+}
+
+impl str::FromStr for FascistStrategy {
+    type Err = Error;
+
+    fn from_str(s : &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "aggressive" => Ok(FascistStrategy::Aggressive),
+            "cautious" => Ok(FascistStrategy::Cautious),
+            _ => Err(Error::BadStrategy {
+                found : s.to_owned(),
+                expected : "aggressive or cautious"
+            })
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub(crate) enum EndingKind {
+    LiberalPolicies,
+    HitlerKilled,
+    FascistPolicies,
+    HitlerChancellor
+}
+
+impl EndingKind {
+    pub(crate) fn liberals_win(&self) -> bool {
+        matches!(self, EndingKind::LiberalPolicies | EndingKind::HitlerKilled)
+    }
+}
+
+impl fmt::Display for EndingKind {
+    fn fmt(&self, f : &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EndingKind::LiberalPolicies => write!(f, "the liberals passing their last policy"),
+            EndingKind::HitlerKilled => write!(f, "Hitler getting killed"),
+            EndingKind::FascistPolicies => write!(f, "the fascists passing their last policy"),
+            EndingKind::HitlerChancellor => write!(f, "Hitler getting elected chancellor")
+        }
+    }
+}
+
+/// How a simulated game ended and the policies on the board at that point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub(crate) struct Ending {
+    pub kind : EndingKind,
+    pub liberal_policies : usize,
+    pub fascist_policies : usize
+}
+
+struct Simulation<'a> {
+    rng : &'a fastrand::Rng,
+    player_state : &'a PlayerState,
+    roles : &'a BTreeMap<PlayerID, SecretRole>,
+    /// (chance of being a liberal, chance of being hitler) according to the
+    /// known information
+    suspicion : &'a BTreeMap<PlayerID, (f64, f64)>,
+    strategies : (LiberalStrategy, FascistStrategy),
+    alive : Vec<PlayerID>,
+    draw_pile : Vec<Policy>,
+    discard_pile : Vec<Policy>,
+    liberal_policies : usize,
+    fascist_policies : usize,
+    term_limited : Vec<PlayerID>
+}
+
+impl<'a> Simulation<'a> {
+    fn is_fascist(&self, player : PlayerID) -> bool { self.roles[&player].is_fascist() }
+
+    fn pick_random(&self, candidates : &[PlayerID]) -> PlayerID {
+        candidates[self.rng.usize(..candidates.len())]
+    }
+
+    /// Picks the candidate that maximizes `score`, ties are broken randomly.
+    fn pick_best(&self, candidates : &[PlayerID], score : impl Fn(PlayerID) -> f64) -> PlayerID {
+        let best_score = candidates
+            .iter()
+            .map(|p| score(*p))
+            .fold(f64::NEG_INFINITY, f64::max);
+        let best = candidates
+            .iter()
+            .copied()
+            .filter(|p| score(*p) >= best_score)
+            .collect_vec();
+        self.pick_random(&best)
+    }
+
+    fn pick_chancellor(&self, president : PlayerID) -> PlayerID {
+        let eligible = self
+            .alive
+            .iter()
+            .copied()
+            .filter(|p| *p != president && !self.term_limited.contains(p))
+            .collect_vec();
+        let hitler_zone = self.fascist_policies
+            >= self
+                .player_state
+                .table_configuration
+                .hitler_zone_passed_fascist_policies;
+
+        if self.is_fascist(president) {
+            let fellow_fascists = eligible
+                .iter()
+                .copied()
+                .filter(|p| self.is_fascist(*p))
+                .collect_vec();
+            let hitler = fellow_fascists
+                .iter()
+                .copied()
+                .find(|p| self.roles[p] == SecretRole::Hitler);
+            match (hitler, self.strategies.1) {
+                (Some(hitler), _) if hitler_zone => hitler,
+                (_, FascistStrategy::Aggressive) if !fellow_fascists.is_empty() => {
+                    self.pick_random(&fellow_fascists)
+                },
+                _ => self.pick_random(&eligible)
+            }
+        }
+        else {
+            match self.strategies.0 {
+                LiberalStrategy::Random => self.pick_random(&eligible),
+                LiberalStrategy::Informed => self.pick_best(&eligible, |p| {
+                    let (liberal, hitler) = self.suspicion[&p];
+                    if hitler_zone {
+                        liberal - hitler
+                    }
+                    else {
+                        liberal
+                    }
+                })
+            }
+        }
+    }
+
+    fn pick_kill(&self, president : PlayerID) -> PlayerID {
+        let targets = self
+            .alive
+            .iter()
+            .copied()
+            .filter(|p| *p != president)
+            .collect_vec();
+
+        let liberals = targets
+            .iter()
+            .copied()
+            .filter(|p| !self.is_fascist(*p))
+            .collect_vec();
+
+        if self.is_fascist(president) && !liberals.is_empty() {
+            self.pick_random(&liberals)
+        }
+        else {
+            match self.strategies.0 {
+                LiberalStrategy::Random => self.pick_random(&targets),
+                LiberalStrategy::Informed => self.pick_best(&targets, |p| self.suspicion[&p].1)
+            }
+        }
+    }
+
+    /// Whether the player discards a liberal policy if given the choice.
+    fn plays_fascist(&self, player : PlayerID, partner : PlayerID) -> bool {
+        self.is_fascist(player)
+            && (self.strategies.1 == FascistStrategy::Aggressive || self.is_fascist(partner))
+    }
+
+    fn draw(&mut self) -> Vec<Policy> {
+        if self.draw_pile.len() < 3 {
+            self.draw_pile.append(&mut self.discard_pile);
+            self.rng.shuffle(&mut self.draw_pile);
+        }
+        self.draw_pile.split_off(self.draw_pile.len() - 3)
+    }
+
+    fn discard(&mut self, hand : &mut Vec<Policy>, keep : Policy) {
+        let discarded = hand
+            .iter()
+            .position(|p| *p != keep)
+            .unwrap_or(hand.len() - 1);
+        self.discard_pile.push(hand.remove(discarded));
+    }
+
+    fn ending(&self, kind : EndingKind) -> Ending {
+        Ending {
+            kind,
+            liberal_policies : self.liberal_policies,
+            fascist_policies : self.fascist_policies
+        }
+    }
+
+    fn run(mut self, mut presidents : impl Iterator<Item = PlayerID>) -> Ending {
+        let fascist_policies_to_win = self
+            .player_state
+            .table_configuration
+            .fascist_board_configuration
+            .len()
+            + 1;
+        let hitler_zone = self
+            .player_state
+            .table_configuration
+            .hitler_zone_passed_fascist_policies;
+
+        loop {
+            let president = presidents
+                .find(|p| self.alive.contains(p))
+                .expect("the rotation never ends");
+            let chancellor = self.pick_chancellor(president);
+            if self.roles[&chancellor] == SecretRole::Hitler && self.fascist_policies >= hitler_zone
+            {
+                return self.ending(EndingKind::HitlerChancellor);
+            }
+            self.term_limited = if self.alive.len() > 5 {
+                vec![president, chancellor]
+            }
+            else {
+                vec![chancellor]
+            };
+
+            let mut hand = self.draw();
+            let president_keeps = if self.plays_fascist(president, chancellor) {
+                Policy::Fascist
+            }
+            else {
+                Policy::Liberal
+            };
+            self.discard(&mut hand, president_keeps);
+            let chancellor_keeps = if self.plays_fascist(chancellor, president) {
+                Policy::Fascist
+            }
+            else {
+                Policy::Liberal
+            };
+            self.discard(&mut hand, chancellor_keeps);
+
+            match hand[0] {
+                Policy::Liberal => {
+                    self.liberal_policies += 1;
+                    if self.liberal_policies >= LIBERAL_POLICIES_TO_WIN {
+                        return self.ending(EndingKind::LiberalPolicies);
+                    }
+                },
+                Policy::Fascist => {
+                    let power = self
+                        .player_state
+                        .table_configuration
+                        .fascist_board_configuration
+                        .get(self.fascist_policies)
+                        .copied();
+                    self.fascist_policies += 1;
+                    if self.fascist_policies >= fascist_policies_to_win {
+                        return self.ending(EndingKind::FascistPolicies);
+                    }
+                    // the other powers only reveal information
+                    if let Some(Kill(_)) = power {
+                        let target = self.pick_kill(president);
+                        if self.roles[&target] == SecretRole::Hitler {
+                            return self.ending(EndingKind::HitlerKilled);
+                        }
+                        self.alive.retain(|p| *p != target);
+                        self.term_limited.retain(|p| *p != target);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Plays the rest of the game `games` times with role assignments drawn from
+/// the ones consistent with the known information and a random deck
+/// consistent with the board. Elections always succeed and neither vetoes nor
+/// the information revealing powers are modelled.
+pub(crate) fn simulate_endings(
+    filter_args : (bool, bool),
+    player_state : &PlayerState,
+    strategies : (LiberalStrategy, FascistStrategy),
+    games : usize
+) -> Result<BTreeMap<Ending, usize>> {
+    let configuration = &player_state.table_configuration;
+    let liberal_policies = player_state.count_policies_on_board(Policy::Liberal);
+    let fascist_policies = player_state.count_policies_on_board(Policy::Fascist);
+    if liberal_policies >= LIBERAL_POLICIES_TO_WIN
+        || fascist_policies > configuration.fascist_board_configuration.len()
+    {
+        return Err(Error::GameAlreadyDecided);
+    }
+
+    let assignments = filter_assigned_roles(filter_args, player_state, &[])?;
+    let suspicion = filtered_histogramm(filter_args, player_state, &[])?
+        .into_iter()
+        .map(|(pid, (roles, _total))| {
+            let probability = |role| roles.get(&role).map_or(0.0, |fr| fr.probability());
+            (
+                pid,
+                (
+                    probability(SecretRole::Liberal),
+                    probability(SecretRole::Hitler)
+                )
+            )
+        })
+        .collect::<BTreeMap<_, _>>();
+    let alive = player_state
+        .player_info
+        .keys()
+        .copied()
+        .filter(|pid| {
+            player_state
+                .player_interactable(*pid, &player_state.player_info)
+                .is_ok()
+        })
+        .collect_vec();
+    let term_limited = match player_state.governments.last() {
+        Some(Election(gov)) if alive.len() > 5 => vec![gov.president, gov.chancellor],
+        Some(Election(gov)) => vec![gov.chancellor],
+        _ => vec![]
+    };
+    // the cards not on the board, only the size of the draw pile is known
+    let off_board = std::iter::repeat_n(
+        Policy::Liberal,
+        configuration.initial_liberal_deck_policies + configuration.initial_placed_liberal_policies
+            - liberal_policies
+    )
+    .chain(std::iter::repeat_n(
+        Policy::Fascist,
+        configuration.initial_fascist_deck_policies + configuration.initial_placed_fascist_policies
+            - fascist_policies
+    ))
+    .collect_vec();
+    let draw_pile_size = player_state
+        .build_next_card_context()?
+        .cards_left
+        .min(off_board.len());
+    let first_president = player_state.next_presidents(1)[0];
+    let table_size = configuration.table_size;
+
+    let rng = fastrand::Rng::new();
+    let mut endings = BTreeMap::new();
+    for _ in 0..games {
+        let mut cards = off_board.clone();
+        rng.shuffle(&mut cards);
+        let draw_pile = cards.split_off(cards.len() - draw_pile_size);
+
+        let simulation = Simulation {
+            rng : &rng,
+            player_state,
+            roles : &assignments[rng.usize(..assignments.len())],
+            suspicion : &suspicion,
+            strategies,
+            alive : alive.clone(),
+            draw_pile,
+            discard_pile : cards,
+            liberal_policies,
+            fascist_policies,
+            term_limited : term_limited.clone()
+        };
+        let presidents = (0..).map(|offset| (first_president - 1 + offset) % table_size + 1);
+        *endings.entry(simulation.run(presidents)).or_insert(0) += 1;
+    }
+
+    Ok(endings)
+}
@@ -10,15 +10,16 @@ use crate::{
 };
 
 use super::{
-    filter_engine::filtered_histogramm, generate_claim_pattern_from_blues, ElectionResult,
-    ElectionResult::*, PlayerInfos, PlayerManager, PlayerState, ShuffleAnalysis
+    filter_engine::filtered_histogramm, format_claim, ElectionResult, ElectionResult::*,
+    PlayerInfos, PlayerManager, PlayerState, ShuffleAnalysis
 };
 
 #[derive(Clone)]
 struct TreeNode {
     relative_probability : FilterResult,
     absolute_probability : f64,
-    original_claimed_blues : usize,
+    /// `None` if the president didn't claim
+    original_claimed_blues : Option<usize>,
     relevant_election_result : ElectionResult,
     children : Vec<TreeNode>
 }
@@ -43,12 +44,12 @@ impl TreeNode {
     fn invariant(&self) -> bool { Self::probability_check_recursive(&self.children) }
 
     fn pres_guaranteed_fasc(&self) -> bool {
-        !matches!(&self.relevant_election_result, Election(eg) if eg.president_claimed_blues == self.original_claimed_blues)
+        !matches!(&self.relevant_election_result, Election(eg) if self.original_claimed_blues.is_none_or(|claimed| eg.president_claimed_blues == Some(claimed)))
             && !matches!(&self.relevant_election_result, TopDeck(_, _))
     }
 
     fn guaranteed_fasc_chancellor(&self) -> bool {
-        matches!(&self.relevant_election_result, Election(eg) if (eg.president_claimed_blues as i64 - eg.chancellor_claimed_blues as i64).abs() > 1)
+        matches!(&self.relevant_election_result, Election(eg) if eg.president_claimed_blues.zip(eg.chancellor_claimed_blues).is_some_and(|(drawn, claimed)| drawn.abs_diff(claimed) > 1))
     }
 }
 
@@ -251,7 +252,7 @@ fn annotate_trees_relative_recursive(
 
         let _ = out_vec[depth].insert({
             let mut val = BTreeSet::new();
-            val.insert(node.relevant_election_result.seen_blues());
+            val.extend(node.relevant_election_result.seen_blues());
             val
         });
 
@@ -306,7 +307,7 @@ fn annotate_trees_relative_recursive(
 
         let _ = follow_on_card_constraints[depth].insert({
             let mut val = BTreeSet::new();
-            val.insert(node.relevant_election_result.seen_blues());
+            val.extend(node.relevant_election_result.seen_blues());
             val
         });
 
@@ -380,11 +381,11 @@ fn draw_tree_recursive(
         TopDeck(p, _) => format!("Top-Deck: {p}"),
         Election(eg) => format!(
             "Assumed Draw: {}\\nPresident {}: {}\\nChancellor {}: {}",
-            generate_claim_pattern_from_blues(eg.president_claimed_blues, 3),
+            format_claim(eg.president_claimed_blues, 3),
             player_info.format_name(eg.president),
-            generate_claim_pattern_from_blues(node.original_claimed_blues, 3),
+            format_claim(node.original_claimed_blues, 3),
             player_info.format_name(eg.chancellor),
-            generate_claim_pattern_from_blues(eg.chancellor_claimed_blues, 2)
+            format_claim(eg.chancellor_claimed_blues, 2)
         )
     };
 
@@ -440,7 +441,7 @@ fn recursively_generate_tree<'a>(
                 let mut out_node = TreeNode {
                     relative_probability : FilterResult::none(1),
                     absolute_probability : 0.0,
-                    original_claimed_blues : passed_blues,
+                    original_claimed_blues : Some(passed_blues),
                     relevant_election_result : (*er).clone(),
                     children : vec![]
                 };
@@ -452,7 +453,7 @@ fn recursively_generate_tree<'a>(
                 .map(|x| x + passed_blues)
                 .map(|nbc| {
                     let mut copy = eg.clone();
-                    copy.president_claimed_blues = nbc;
+                    copy.president_claimed_blues = Some(nbc);
                     copy
                 })
                 .map(|neg| {